@@ -1,56 +1,217 @@
 use crate::adb::executor::AdbExecutor;
+use crate::adb::storage::AndroidStorage;
 use crate::command_utils::TokioCommandExt;
-use crate::error::AppError;
+use crate::error::{AdbError, AppError};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+
+/// Timeout for a single in-flight request waiting on its response frame.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reject a frame length bigger than this before allocating its buffer. Agent responses are
+/// small JSON payloads (app lists, file listings, icons); a compromised or misbehaving agent
+/// process sending a bogus length shouldn't be able to force an unbounded allocation here.
+const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+/// A pooled connection to one device's agent: a writer half guarded by a lock (so concurrent
+/// callers serialize their frame writes) and a background task that reads response frames off
+/// the read half and dispatches each one to whichever caller is waiting on its request id.
+struct AgentConnection {
+    writer: tokio::sync::Mutex<OwnedWriteHalf>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+    next_id: AtomicU64,
+}
+
+impl AgentConnection {
+    async fn connect(addr: &str) -> Result<Self, AppError> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+        let (read_half, write_half) = stream.into_split();
+
+        let pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(Self::dispatch_loop(read_half, pending.clone()));
+
+        Ok(Self {
+            writer: tokio::sync::Mutex::new(write_half),
+            pending,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    /// Reads length-prefixed response frames off the socket for as long as it stays open,
+    /// matching each one to a pending request by its `id` field and waking the caller.
+    async fn dispatch_loop(
+        mut read_half: OwnedReadHalf,
+        pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
+    ) {
+        loop {
+            let frame = match read_frame(&mut read_half).await {
+                Ok(frame) => frame,
+                Err(_) => break,
+            };
+
+            let Ok(response) = serde_json::from_slice::<Value>(&frame) else {
+                continue;
+            };
+
+            if let Some(id) = response.get("id").and_then(Value::as_u64) {
+                if let Some(sender) = pending.lock().unwrap().remove(&id) {
+                    let _ = sender.send(response);
+                }
+            }
+        }
+
+        // The socket is gone - wake every still-pending caller instead of leaving them to time
+        // out. Dropping each sender fails its call, which evicts this connection from the pool
+        // so the next call reconnects.
+        for (_, sender) in pending.lock().unwrap().drain() {
+            drop(sender);
+        }
+    }
+
+    async fn send_command(&self, cmd_type: &str, data: Value) -> Result<Value, AppError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = json!({ "id": id, "type": cmd_type, "data": data });
+        let frame = encode_frame(&request)?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        {
+            let mut writer = self.writer.lock().await;
+            if let Err(e) = writer.write_all(&frame).await {
+                self.pending.lock().unwrap().remove(&id);
+                return Err(AppError::from(AdbError::ExecutionFailed(e.to_string())));
+            }
+        }
+
+        match tokio::time::timeout(RESPONSE_TIMEOUT, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(AppError::from(AdbError::ExecutionFailed(
+                "Agent connection closed before responding".to_string(),
+            ))),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(AppError::from(AdbError::ExecutionFailed(
+                    "Agent response timed out".to_string(),
+                )))
+            }
+        }
+    }
+}
+
+/// Frame a JSON payload as a 4-byte big-endian length prefix followed by the payload bytes,
+/// mirroring how the ADB wire protocol frames its own messages.
+fn encode_frame(value: &Value) -> Result<Vec<u8>, AppError> {
+    let payload = serde_json::to_vec(value)
+        .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("JSON encode error: {}", e))))?;
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Read one length-prefixed frame's payload off `reader`.
+async fn read_frame(reader: &mut OwnedReadHalf) -> Result<Vec<u8>, AppError> {
+    let mut len_buf = [0u8; 4];
+    reader
+        .read_exact(&mut len_buf)
+        .await
+        .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("Failed to read frame length: {}", e))))?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(AppError::from(AdbError::ExecutionFailed(format!(
+            "Agent frame length {} exceeds the {}-byte limit",
+            len, MAX_FRAME_SIZE
+        ))));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader
+        .read_exact(&mut payload)
+        .await
+        .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("Failed to read frame payload: {}", e))))?;
+
+    Ok(payload)
+}
 
 pub struct AgentManager {
     executor: AdbExecutor,
-    port: u16,
+    /// Port the agent process listens on inside every device. Safe to share across devices
+    /// since it's never dialed directly - only via each device's own local-forwarded port.
+    remote_port: u16,
+    storage: AndroidStorage,
+    connections: Mutex<HashMap<String, Arc<AgentConnection>>>,
+    /// Local port `adb forward` assigned for each device's agent, keyed by device id. Each
+    /// device gets its own ephemeral local port so two devices forwarding at once can't collide
+    /// on the same loopback address.
+    local_ports: Mutex<HashMap<String, u16>>,
 }
 
 impl AgentManager {
+    /// Resolves the agent jar's remote directory via `AndroidStorage::Auto`, so devices where
+    /// `/data/local/tmp` isn't writable still get a working agent.
     pub fn new(executor: AdbExecutor) -> Self {
         Self {
             executor,
-            port: 12345,
+            remote_port: 12345,
+            storage: AndroidStorage::Auto,
+            connections: Mutex::new(HashMap::new()),
+            local_ports: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Prepare and start the agent on the specified device.
-    pub async fn start_agent(&self, device_id: &str) -> Result<(), AppError> {
+    /// Like `new`, but resolves the agent jar's remote directory against a specific storage
+    /// location instead of probing for the first writable one.
+    pub fn with_storage(executor: AdbExecutor, storage: AndroidStorage) -> Self {
+        Self {
+            executor,
+            remote_port: 12345,
+            storage,
+            connections: Mutex::new(HashMap::new()),
+            local_ports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Prepare and start the agent on the specified device, returning the local port its
+    /// forward was assigned to.
+    async fn start_agent(&self, device_id: &str) -> Result<u16, AppError> {
         // 1. Push the JAR to the device
         // Assume the jar is in binaries folder
         let agent_path = "binaries/agent.jar";
 
+        let remote_dir = self.storage.resolve_root(device_id)?;
+        let remote_jar = format!("{}/agent.jar", remote_dir.trim_end_matches('/'));
+
         let adb_path = self.executor.get_adb_path();
 
-        // Push command: adb -s <id> push <path> /data/local/tmp/agent.jar
+        // Push command: adb -s <id> push <path> <remote_jar>
         let output = tokio::process::Command::new(adb_path)
             .hide_window()
-            .args([
-                "-s",
-                device_id,
-                "push",
-                agent_path,
-                "/data/local/tmp/agent.jar",
-            ])
+            .args(["-s", device_id, "push", agent_path, &remote_jar])
             .output()
             .await
-            .map_err(|e| AppError::from(crate::error::AdbError::ExecutionFailed(e.to_string())))?;
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
 
         if !output.status.success() {
-            return Err(AppError::from(crate::error::AdbError::ExecutionFailed(
+            return Err(AppError::from(AdbError::ExecutionFailed(
                 String::from_utf8_lossy(&output.stderr).to_string(),
             )));
         }
 
         // 2. Start the agent using app_process
         let start_cmd = format!(
-            "CLASSPATH=/data/local/tmp/agent.jar app_process / com.h1dr0n.adbcompass.Main {}",
-            self.port
+            "CLASSPATH={} app_process / com.h1dr0n.adbcompass.Main {}",
+            remote_jar, self.remote_port
         );
 
         // We start it in background
@@ -58,102 +219,114 @@ impl AgentManager {
             .hide_window()
             .args(["-s", device_id, "shell", &start_cmd])
             .spawn()
-            .map_err(|e| AppError::from(crate::error::AdbError::ExecutionFailed(e.to_string())))?;
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
 
-        // 3. Setup port forwarding
+        // 3. Setup port forwarding onto an ephemeral local port (`tcp:0`), so a second device
+        // doesn't silently steal the first device's local port and start talking to the wrong
+        // agent. `adb forward` prints the port it actually bound on stdout.
         let forward_output = tokio::process::Command::new(adb_path)
             .hide_window()
             .args([
                 "-s",
                 device_id,
                 "forward",
-                &format!("tcp:{}", self.port),
-                &format!("tcp:{}", self.port),
+                "tcp:0",
+                &format!("tcp:{}", self.remote_port),
             ])
             .output()
             .await
-            .map_err(|e| AppError::from(crate::error::AdbError::ExecutionFailed(e.to_string())))?;
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
 
         if !forward_output.status.success() {
-            return Err(AppError::from(crate::error::AdbError::ExecutionFailed(
+            return Err(AppError::from(AdbError::ExecutionFailed(
                 String::from_utf8_lossy(&forward_output.stderr).to_string(),
             )));
         }
 
+        let local_port: u16 = String::from_utf8_lossy(&forward_output.stdout)
+            .trim()
+            .parse()
+            .map_err(|_| {
+                AppError::from(AdbError::ParseError(format!(
+                    "Could not parse local port from `adb forward` output: {:?}",
+                    String::from_utf8_lossy(&forward_output.stdout)
+                )))
+            })?;
+
+        self.local_ports
+            .lock()
+            .unwrap()
+            .insert(device_id.to_string(), local_port);
+
         // Give it a moment to start
         tokio::time::sleep(Duration::from_millis(1500)).await;
 
-        Ok(())
-    }
-
-    /// Ensures the agent is running and connected. If not, attempts to start it.
-    async fn ensure_agent(&self, device_id: &str) -> Result<TcpStream, AppError> {
-        let addr = format!("127.0.0.1:{}", self.port);
-
-        // Try to connect first
-        match tokio::time::timeout(Duration::from_secs(1), TcpStream::connect(&addr)).await {
-            Ok(Ok(stream)) => Ok(stream),
-            _ => {
-                // Connection failed, try to start the agent
-                self.start_agent(device_id).await?;
-
-                // Try to connect again after starting
-                tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(&addr))
-                    .await
-                    .map_err(|_| {
-                        AppError::from(crate::error::AdbError::ExecutionFailed(
-                            "Failed to connect to agent after start timeout".to_string(),
-                        ))
-                    })?
-                    .map_err(|e| {
-                        AppError::from(crate::error::AdbError::ExecutionFailed(format!(
-                            "Socket connect failed after agent start: {}",
-                            e
-                        )))
-                    })
+        Ok(local_port)
+    }
+
+    /// Returns the pooled connection for `device_id`, reconnecting (and starting the agent if
+    /// needed) when there isn't one yet. Each device is dialed on its own forwarded local port
+    /// so concurrently connected devices never cross-talk.
+    async fn ensure_agent(&self, device_id: &str) -> Result<Arc<AgentConnection>, AppError> {
+        if let Some(conn) = self.connections.lock().unwrap().get(device_id).cloned() {
+            return Ok(conn);
+        }
+
+        let existing_port = self.local_ports.lock().unwrap().get(device_id).copied();
+
+        if let Some(port) = existing_port {
+            let addr = format!("127.0.0.1:{}", port);
+            if let Ok(Ok(conn)) =
+                tokio::time::timeout(Duration::from_secs(1), AgentConnection::connect(&addr)).await
+            {
+                let conn = Arc::new(conn);
+                self.connections
+                    .lock()
+                    .unwrap()
+                    .insert(device_id.to_string(), conn.clone());
+                return Ok(conn);
             }
         }
+
+        // No pooled connection, and either no forward yet or the old one stopped answering -
+        // (re)start the agent, which reassigns this device's local port.
+        let port = self.start_agent(device_id).await?;
+        let addr = format!("127.0.0.1:{}", port);
+
+        let conn = tokio::time::timeout(Duration::from_secs(2), AgentConnection::connect(&addr))
+            .await
+            .map_err(|_| {
+                AppError::from(AdbError::ExecutionFailed(
+                    "Failed to connect to agent after start timeout".to_string(),
+                ))
+            })??;
+
+        let conn = Arc::new(conn);
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(device_id.to_string(), conn.clone());
+        Ok(conn)
     }
 
-    /// Send a command to the agent and receive a response.
+    /// Send a command to the agent and receive a response, over a pooled long-lived connection
+    /// shared by all commands for this device. A broken connection is dropped from the pool so
+    /// the next call reconnects instead of repeatedly failing against a dead socket.
     pub async fn send_command(
         &self,
         device_id: &str,
         cmd_type: &str,
         data: Value,
     ) -> Result<Value, AppError> {
-        let mut stream = self.ensure_agent(device_id).await?;
-
-        let request = json!({
-            "type": cmd_type,
-            "data": data
-        });
-
-        let mut request_str = request.to_string();
-        request_str.push('\n');
+        let conn = self.ensure_agent(device_id).await?;
 
-        stream
-            .write_all(request_str.as_bytes())
-            .await
-            .map_err(|e| AppError::from(crate::error::AdbError::ExecutionFailed(e.to_string())))?;
-
-        let mut reader = BufReader::new(stream);
-        let mut response_str = String::new();
-        reader.read_line(&mut response_str).await.map_err(|e| {
-            AppError::from(crate::error::AdbError::ExecutionFailed(format!(
-                "Read failure: {}",
-                e
-            )))
-        })?;
-
-        let response: Value = serde_json::from_str(&response_str).map_err(|e| {
-            AppError::from(crate::error::AdbError::ExecutionFailed(format!(
-                "JSON parse error: {}",
-                e
-            )))
-        })?;
-
-        Ok(response)
+        match conn.send_command(cmd_type, data).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                self.connections.lock().unwrap().remove(device_id);
+                Err(e)
+            }
+        }
     }
 
     pub async fn list_files_fast(&self, device_id: &str, path: &str) -> Result<Value, AppError> {
@@ -0,0 +1,346 @@
+// ADB Bootstrap - Downloads and installs Google's official platform-tools when no working
+// `adb` can be found on a clean machine, so the tracker, installer, and requirement checks all
+// have something to run against without the user hunting down platform-tools themselves.
+
+use crate::apk::manifest::zip;
+use crate::command_utils::hidden_command;
+use crate::error::{AdbError, AppError};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Stage of the bootstrap process, for progress events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStage {
+    Downloading,
+    Verifying,
+    Extracting,
+    Done,
+}
+
+/// Progress payload reported while platform-tools is fetched and unpacked.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BootstrapProgress {
+    pub stage: BootstrapStage,
+    pub bytes_done: u64,
+    pub bytes_total: Option<u64>,
+}
+
+/// Directory platform-tools is extracted into, independent of the app's current working
+/// directory (that's the whole point - the app may be launched from anywhere).
+pub fn managed_install_dir() -> PathBuf {
+    managed_data_dir().join("platform-tools")
+}
+
+/// Path to the managed `adb` binary, whether or not it's been downloaded yet.
+pub fn managed_adb_path() -> PathBuf {
+    let exe_name = if cfg!(target_os = "windows") {
+        "adb.exe"
+    } else {
+        "adb"
+    };
+    managed_install_dir().join(exe_name)
+}
+
+/// A user-writable data directory for the app, computed without pulling in Tauri's path
+/// resolver (which needs an `AppHandle`, not available from the static `AdbClient::discover_adb`
+/// path). Mirrors the OS conventions Tauri itself uses for `app_data_dir`.
+fn managed_data_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("adb-compass")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        dirs_home()
+            .join("Library")
+            .join("Application Support")
+            .join("adb-compass")
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| dirs_home().join(".local").join("share"))
+            .join("adb-compass")
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn dirs_home() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Check whether the binary at `path` is a working `adb` by running `adb version`.
+pub fn is_adb_working(path: &Path) -> bool {
+    hidden_command(path)
+        .arg("version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Ensure a working `adb` is available, downloading and installing platform-tools if needed.
+/// Returns the path to the working `adb` binary. Pass `force` to re-download even if a managed
+/// copy already exists (e.g. it's been reported corrupt).
+pub fn ensure_adb(
+    force: bool,
+    mut on_progress: impl FnMut(&BootstrapProgress),
+) -> Result<PathBuf, AppError> {
+    let managed_path = managed_adb_path();
+
+    if !force && is_adb_working(&managed_path) {
+        return Ok(managed_path);
+    }
+
+    let url = platform_tools_url();
+
+    on_progress(&BootstrapProgress {
+        stage: BootstrapStage::Downloading,
+        bytes_done: 0,
+        bytes_total: None,
+    });
+    let archive = download(&url, &mut on_progress)?;
+
+    on_progress(&BootstrapProgress {
+        stage: BootstrapStage::Verifying,
+        bytes_done: 0,
+        bytes_total: None,
+    });
+    let entries = zip::read_entries(&archive).ok_or_else(|| {
+        AppError::from(AdbError::BootstrapFailed(
+            "Downloaded archive is not a valid zip".into(),
+        ))
+    })?;
+
+    let adb_entry_name = if cfg!(target_os = "windows") {
+        "platform-tools/adb.exe"
+    } else {
+        "platform-tools/adb"
+    };
+    if !entries.iter().any(|e| e.name == adb_entry_name) {
+        return Err(AppError::from(AdbError::BootstrapFailed(format!(
+            "Archive did not contain {adb_entry_name}"
+        ))));
+    }
+    verify_entries(&archive, &entries)?;
+
+    on_progress(&BootstrapProgress {
+        stage: BootstrapStage::Extracting,
+        bytes_done: 0,
+        bytes_total: Some(entries.len() as u64),
+    });
+    extract(&archive, &entries, &mut on_progress)?;
+
+    on_progress(&BootstrapProgress {
+        stage: BootstrapStage::Done,
+        bytes_done: 1,
+        bytes_total: Some(1),
+    });
+
+    if !is_adb_working(&managed_path) {
+        return Err(AppError::from(AdbError::BootstrapFailed(
+            "Extracted adb binary did not run successfully".into(),
+        )));
+    }
+
+    Ok(managed_path)
+}
+
+/// Official platform-tools archive name for the current OS, per Google's naming scheme.
+fn platform_tools_url() -> String {
+    let os = if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "darwin"
+    } else {
+        "linux"
+    };
+    format!("https://dl.google.com/android/repository/platform-tools-latest-{os}.zip")
+}
+
+fn download(
+    url: &str,
+    on_progress: &mut impl FnMut(&BootstrapProgress),
+) -> Result<Vec<u8>, AppError> {
+    let response = ureq::get(url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .call()
+        .map_err(|e| AppError::from(AdbError::BootstrapFailed(format!("Download failed: {e}"))))?;
+
+    let bytes_total = response
+        .header("Content-Length")
+        .and_then(|s| s.parse::<u64>().ok());
+
+    let mut reader = response.into_reader();
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    let mut bytes_done: u64 = 0;
+
+    loop {
+        let read = reader
+            .read(&mut chunk)
+            .map_err(|e| AppError::from(AdbError::BootstrapFailed(format!("Download failed: {e}"))))?;
+        if read == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        bytes_done += read as u64;
+        on_progress(&BootstrapProgress {
+            stage: BootstrapStage::Downloading,
+            bytes_done,
+            bytes_total,
+        });
+    }
+
+    Ok(buf)
+}
+
+/// Confirm every entry's data actually matches the CRC-32 the archive's own central directory
+/// records for it, so a truncated or corrupted download is caught here - during the
+/// `Verifying` stage - instead of surfacing later as a broken `adb` binary or being extracted
+/// unnoticed.
+fn verify_entries(archive: &[u8], entries: &[zip::ZipEntry]) -> Result<(), AppError> {
+    for entry in entries {
+        if entry.name.ends_with('/') {
+            continue;
+        }
+
+        let data = zip::read_entry_data(archive, entry).ok_or_else(|| {
+            AppError::from(AdbError::BootstrapFailed(format!(
+                "Could not read {} from archive",
+                entry.name
+            )))
+        })?;
+
+        if zip::crc32(&data) != entry.crc32 {
+            return Err(AppError::from(AdbError::BootstrapFailed(format!(
+                "Checksum mismatch for {} - archive may be corrupt",
+                entry.name
+            ))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject an entry's relative path if any component would escape `install_dir` (e.g. `..`) or
+/// re-root it (an absolute path) once joined - a malicious or corrupt archive shouldn't be able
+/// to write outside the directory platform-tools is meant to unpack into.
+fn is_safe_relative_path(relative: &str) -> bool {
+    Path::new(relative)
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+fn extract(
+    archive: &[u8],
+    entries: &[zip::ZipEntry],
+    on_progress: &mut impl FnMut(&BootstrapProgress),
+) -> Result<(), AppError> {
+    let install_dir = managed_install_dir();
+    let parent = install_dir.parent().unwrap_or(&install_dir);
+    std::fs::create_dir_all(parent).map_err(|e| {
+        AppError::from(AdbError::BootstrapFailed(format!(
+            "Could not create install directory: {e}"
+        )))
+    })?;
+
+    for (i, entry) in entries.iter().enumerate() {
+        // Entries under "platform-tools/..."; drop that prefix so it lands directly in
+        // `managed_install_dir()`.
+        let relative = match entry.name.strip_prefix("platform-tools/") {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => continue,
+        };
+        if entry.name.ends_with('/') {
+            continue;
+        }
+        if !is_safe_relative_path(relative) {
+            return Err(AppError::from(AdbError::BootstrapFailed(format!(
+                "Archive entry has an unsafe path: {}",
+                entry.name
+            ))));
+        }
+
+        let dest = install_dir.join(relative);
+        if let Some(dest_parent) = dest.parent() {
+            std::fs::create_dir_all(dest_parent).map_err(|e| {
+                AppError::from(AdbError::BootstrapFailed(format!(
+                    "Could not create directory for {relative}: {e}"
+                )))
+            })?;
+        }
+
+        let data = zip::read_entry_data(archive, entry).ok_or_else(|| {
+            AppError::from(AdbError::BootstrapFailed(format!(
+                "Could not read {relative} from archive"
+            )))
+        })?;
+
+        let mut file = std::fs::File::create(&dest).map_err(|e| {
+            AppError::from(AdbError::BootstrapFailed(format!(
+                "Could not write {relative}: {e}"
+            )))
+        })?;
+        file.write_all(&data).map_err(|e| {
+            AppError::from(AdbError::BootstrapFailed(format!(
+                "Could not write {relative}: {e}"
+            )))
+        })?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(0o755));
+        }
+
+        on_progress(&BootstrapProgress {
+            stage: BootstrapStage::Extracting,
+            bytes_done: (i + 1) as u64,
+            bytes_total: Some(entries.len() as u64),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_managed_adb_path_under_install_dir() {
+        assert_eq!(managed_adb_path().parent().unwrap(), managed_install_dir());
+    }
+
+    #[test]
+    fn test_platform_tools_url_contains_zip_suffix() {
+        assert!(platform_tools_url().ends_with(".zip"));
+    }
+
+    #[test]
+    fn test_is_safe_relative_path_accepts_plain_names() {
+        assert!(is_safe_relative_path("adb"));
+        assert!(is_safe_relative_path("lib64/libc++.so"));
+    }
+
+    #[test]
+    fn test_is_safe_relative_path_rejects_traversal() {
+        assert!(!is_safe_relative_path("../../etc/cron.d/evil"));
+        assert!(!is_safe_relative_path("a/../../b"));
+    }
+
+    #[test]
+    fn test_is_safe_relative_path_rejects_absolute_paths() {
+        assert!(!is_safe_relative_path("/etc/passwd"));
+    }
+}
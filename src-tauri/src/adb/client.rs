@@ -1,10 +1,11 @@
 // ADB Client - Low-level ADB process execution
 // Handles finding ADB path, command execution with timeouts and retries.
 
+use crate::adb::protocol::{self, AdbProtocolClient, DEFAULT_SERVER_ADDR};
 use crate::command_utils::hidden_command;
 use crate::error::{AdbError, AppError};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output, Stdio};
+use std::process::{Command, ExitStatus, Output, Stdio};
 use std::time::Duration;
 use wait_timeout::ChildExt;
 
@@ -34,15 +35,31 @@ impl Default for ExecutionConfig {
 /// A low-level client for executing ADB commands.
 /// This client is responsible for managing the ADB executable path and
 /// ensuring commands are executed safely across different platforms.
+///
+/// `execute`/`execute_with_config` normally spawn the `adb` binary per call, but a client can
+/// instead be pinned to an already-running ADB server address (see `connect_server`), in which
+/// case the same two methods speak the host wire protocol directly over the connection instead
+/// of forking a process.
 pub struct AdbClient {
     adb_path: PathBuf,
+    server_addr: Option<String>,
 }
 
 impl AdbClient {
-    /// Initialize a new ADB client, automatically discovering the ADB path.
+    /// Initialize a new ADB client, automatically discovering the ADB path. Falls back to
+    /// talking to an already-running server over the wire protocol if no bundled/managed
+    /// binary can be found and one is reachable at the default address.
     pub fn new() -> Self {
+        let adb_path = Self::discover_adb();
+        let server_addr = if adb_path == PathBuf::from("adb") {
+            Self::probe_server(DEFAULT_SERVER_ADDR)
+        } else {
+            None
+        };
+
         Self {
-            adb_path: Self::discover_adb(),
+            adb_path,
+            server_addr,
         }
     }
 
@@ -50,14 +67,39 @@ impl AdbClient {
     pub fn with_path<P: AsRef<Path>>(path: P) -> Self {
         Self {
             adb_path: path.as_ref().to_path_buf(),
+            server_addr: None,
         }
     }
 
+    /// Initialize a client pinned to an already-running ADB server's host protocol, bypassing
+    /// process spawning entirely. Probes `addr` with `host:version` so construction fails fast
+    /// if nothing is listening there.
+    pub fn connect_server(addr: &str) -> Result<Self, AppError> {
+        protocol::host_request_at(addr, "host:version")?;
+        Ok(Self {
+            adb_path: PathBuf::from("adb"),
+            server_addr: Some(addr.to_string()),
+        })
+    }
+
+    /// Check whether a server is reachable at `addr` without failing construction if it isn't.
+    fn probe_server(addr: &str) -> Option<String> {
+        protocol::host_request_at(addr, "host:version")
+            .ok()
+            .map(|_| addr.to_string())
+    }
+
     /// Get the path to the ADB executable being used.
     pub fn adb_path(&self) -> &PathBuf {
         &self.adb_path
     }
 
+    /// Address of the ADB server this client talks to directly over the wire protocol, if it's
+    /// using that backend instead of spawning the CLI per call.
+    pub fn server_addr(&self) -> Option<&str> {
+        self.server_addr.as_deref()
+    }
+
     /// Legend/Legacy support for direct Command builders.
     pub fn run_with_retry<F>(
         &self,
@@ -98,6 +140,10 @@ impl AdbClient {
         args: &[&str],
         config: &ExecutionConfig,
     ) -> Result<Output, AppError> {
+        if let Some(addr) = &self.server_addr {
+            return Self::execute_over_server(addr, args, config);
+        }
+
         let mut last_error = AppError::from(AdbError::ExecutionFailed("No attempts made".into()));
 
         for attempt in 0..=config.retries {
@@ -122,6 +168,73 @@ impl AdbClient {
         Err(last_error)
     }
 
+    /// Run `args` over an already-connected ADB server instead of spawning the CLI, retrying
+    /// the same number of times `execute_with_config` would.
+    fn execute_over_server(
+        addr: &str,
+        args: &[&str],
+        config: &ExecutionConfig,
+    ) -> Result<Output, AppError> {
+        let mut last_error = AppError::from(AdbError::ExecutionFailed("No attempts made".into()));
+
+        for attempt in 0..=config.retries {
+            if attempt > 0 {
+                std::thread::sleep(Duration::from_millis(1000));
+            }
+
+            match Self::run_over_server(addr, args, config.timeout) {
+                Ok(output) => return Ok(output),
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Translate a CLI-shaped argument list into a single host-protocol request, run it over a
+    /// fresh connection to `addr`, and package the reply as a synthetic `Output`. Only the
+    /// handful of command shapes this codebase actually passes through `AdbClient::execute` are
+    /// supported (see `ServerOp::for_args`) — anything else is reported as a clear error rather
+    /// than silently falling back to a process spawn.
+    fn run_over_server(addr: &str, args: &[&str], timeout: Duration) -> Result<Output, AppError> {
+        let (serial, rest) = match args {
+            ["-s", serial, rest @ ..] => (Some(*serial), rest),
+            rest => (None, rest),
+        };
+
+        let op = ServerOp::for_args(rest).ok_or_else(|| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Command {:?} has no server-backend equivalent",
+                args
+            )))
+        })?;
+
+        let mut client = AdbProtocolClient::connect(addr)?;
+        client.set_timeout(timeout)?;
+
+        let stdout = match op {
+            ServerOp::Host(service) => {
+                client.send_request(&service)?;
+                client.read_to_end()?
+            }
+            ServerOp::Device(service) => {
+                let serial = serial.ok_or_else(|| {
+                    AppError::from(AdbError::ExecutionFailed(format!(
+                        "Command {:?} needs a target device (-s <serial>)",
+                        args
+                    )))
+                })?;
+                client.run_device_service(serial, &service)?
+            }
+        };
+
+        Ok(Output {
+            status: success_exit_status(),
+            stdout,
+            stderr: Vec::new(),
+        })
+    }
+
     /// Helper to spawn and wait for a process with timeout.
     fn wait_for_process(&self, cmd: &mut Command, timeout: Duration) -> Result<Output, AppError> {
         cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
@@ -150,9 +263,15 @@ impl AdbClient {
         }
     }
 
-    /// Discover the ADB path by checking bundled locations and the system path.
+    /// Discover the ADB path by checking bundled locations, a previously-bootstrapped
+    /// platform-tools install (see `adb::bootstrap`), and finally the system path.
     fn discover_adb() -> PathBuf {
-        Self::find_bundled_adb().unwrap_or_else(|| PathBuf::from("adb"))
+        Self::find_bundled_adb()
+            .or_else(|| {
+                let managed = crate::adb::bootstrap::managed_adb_path();
+                managed.exists().then_some(managed)
+            })
+            .unwrap_or_else(|| PathBuf::from("adb"))
     }
 
     /// Check for bundled ADB in common application directories.
@@ -190,6 +309,47 @@ impl AdbClient {
     }
 }
 
+/// A host-protocol request derived from a CLI-shaped argument list: either a plain `host:...`
+/// request, or one that needs a `host:transport:<serial>` handshake first.
+enum ServerOp {
+    Host(String),
+    Device(String),
+}
+
+impl ServerOp {
+    /// Map the handful of argument shapes this codebase actually passes to `AdbClient::execute`
+    /// onto their host-protocol equivalents. Returns `None` for anything unsupported (e.g.
+    /// `kill-server`, which has no host-protocol equivalent since it tears down the very server
+    /// this connection depends on).
+    fn for_args(args: &[&str]) -> Option<Self> {
+        match args {
+            ["version"] => Some(Self::Host("host:version".into())),
+            ["devices"] => Some(Self::Host("host:devices".into())),
+            ["devices", "-l"] => Some(Self::Host("host:devices-l".into())),
+            // The server this connection is talking to is already running.
+            ["start-server"] => Some(Self::Host("host:version".into())),
+            ["shell", tail @ ..] if !tail.is_empty() => {
+                Some(Self::Device(format!("shell:{}", tail.join(" "))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Build an `ExitStatus` representing success, for synthesizing `Output` values from
+/// host-protocol replies that never went through an actual child process.
+#[cfg(unix)]
+fn success_exit_status() -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(0)
+}
+
+#[cfg(windows)]
+fn success_exit_status() -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +366,21 @@ mod tests {
         let client = AdbClient::with_path(&path);
         assert_eq!(client.adb_path(), &path);
     }
+
+    #[test]
+    fn test_server_op_for_args_supports_common_shapes() {
+        assert!(matches!(
+            ServerOp::for_args(&["version"]),
+            Some(ServerOp::Host(_))
+        ));
+        assert!(matches!(
+            ServerOp::for_args(&["devices", "-l"]),
+            Some(ServerOp::Host(_))
+        ));
+        assert!(matches!(
+            ServerOp::for_args(&["shell", "getprop", "ro.product.model"]),
+            Some(ServerOp::Device(_))
+        ));
+        assert!(ServerOp::for_args(&["kill-server"]).is_none());
+    }
 }
@@ -15,6 +15,7 @@ pub enum AdbCommand {
     StartServer,
     KillServer,
     GetProp(String),
+    Sideload { zip_path: String },
 }
 
 impl AdbCommand {
@@ -66,6 +67,7 @@ impl AdbCommand {
             AdbCommand::StartServer => vec!["start-server".into()],
             AdbCommand::KillServer => vec!["kill-server".into()],
             AdbCommand::GetProp(prop) => vec!["shell".into(), "getprop".into(), prop.clone()],
+            AdbCommand::Sideload { zip_path } => vec!["sideload".into(), zip_path.clone()],
         }
     }
 }
@@ -117,11 +119,37 @@ impl ShellCommandBuilder {
         self
     }
 
+    /// Add an argument, quoting it first if it contains characters outside the safe set.
+    /// See [`quote_shell_arg`] for the quoting rules.
+    pub fn quoted_arg(mut self, value: &str) -> Self {
+        self.args.push(quote_shell_arg(value));
+        self
+    }
+
     pub fn build(self) -> Vec<String> {
         self.args
     }
 }
 
+/// Quote a single shell argument for safe inclusion in an `adb shell` command line.
+///
+/// Arguments made entirely of the safe character set `[A-Za-z0-9_@%+=:,./-]` are passed
+/// through unchanged; anything else is wrapped in single quotes, with embedded single quotes
+/// escaped as `'\''`, so spaces, `;`, `&&`, `$`, and other shell metacharacters can't break out
+/// of the argument or inject additional commands.
+pub fn quote_shell_arg(value: &str) -> String {
+    let is_safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+
+    if is_safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +176,40 @@ mod tests {
             .build();
         assert_eq!(args, vec!["input", "tap", "100", "200"]);
     }
+
+    #[test]
+    fn test_quote_shell_arg_safe_passthrough() {
+        assert_eq!(quote_shell_arg("/sdcard/Download"), "/sdcard/Download");
+        assert_eq!(quote_shell_arg("file_v1.2+build.apk"), "file_v1.2+build.apk");
+    }
+
+    #[test]
+    fn test_quote_shell_arg_escapes_spaces() {
+        assert_eq!(quote_shell_arg("my file.txt"), "'my file.txt'");
+    }
+
+    #[test]
+    fn test_quote_shell_arg_escapes_quotes_and_injection() {
+        assert_eq!(quote_shell_arg("it's"), "'it'\\''s'");
+        assert_eq!(
+            quote_shell_arg("/sdcard/a; rm -rf /"),
+            "'/sdcard/a; rm -rf /'"
+        );
+    }
+
+    #[test]
+    fn test_quote_shell_arg_escapes_dollar_and_glob() {
+        assert_eq!(quote_shell_arg("$(reboot)"), "'$(reboot)'");
+        assert_eq!(quote_shell_arg("*.apk"), "'*.apk'");
+        assert_eq!(quote_shell_arg("a && rm -rf /"), "'a && rm -rf /'");
+    }
+
+    #[test]
+    fn test_quoted_arg_on_builder() {
+        let args = ShellCommandBuilder::new("rm")
+            .arg("-rf")
+            .quoted_arg("/sdcard/my file.txt")
+            .build();
+        assert_eq!(args, vec!["rm", "-rf", "'/sdcard/my file.txt'"]);
+    }
 }
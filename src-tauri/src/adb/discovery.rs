@@ -3,7 +3,8 @@
 
 use crate::adb::client::AdbClient;
 use crate::adb::command_builder::{AdbCommand, AdbCommandBuilder};
-use crate::adb::executor::{DeviceInfo, DeviceStatus};
+use crate::adb::executor::{transport_for_id, DeviceInfo, DeviceStatus};
+use crate::adb::protocol::AdbProtocolClient;
 use crate::error::AppError;
 
 /// Handles discovering and identifying connected Android devices.
@@ -22,7 +23,7 @@ impl<'a> AdbDiscovery<'a> {
         let output = self.client.execute(&["devices", "-l"])?;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
-        let mut devices = self.parse_devices_output(&stdout);
+        let mut devices = Self::parse_devices_output(&stdout);
 
         // Enrich device info for connected devices
         for device in &mut devices {
@@ -36,11 +37,18 @@ impl<'a> AdbDiscovery<'a> {
         Ok(devices)
     }
 
-    /// Parse the output of `adb devices -l`.
-    fn parse_devices_output(&self, output: &str) -> Vec<DeviceInfo> {
+    /// Parse the output of `adb devices -l`, which starts with a `List of devices attached`
+    /// header line that the CLI tool prints before the actual device list.
+    pub(crate) fn parse_devices_output(output: &str) -> Vec<DeviceInfo> {
+        Self::parse_devices_lines(output.lines().skip(1))
+    }
+
+    /// Parse bare device-list lines with no header, e.g. the snapshots pushed by the
+    /// `host:track-devices` service over the wire protocol.
+    pub(crate) fn parse_devices_lines<'l>(lines: impl Iterator<Item = &'l str>) -> Vec<DeviceInfo> {
         let mut devices = Vec::new();
 
-        for line in output.lines().skip(1) {
+        for line in lines {
             let line = line.trim();
             if line.is_empty() {
                 continue;
@@ -65,20 +73,65 @@ impl<'a> AdbDiscovery<'a> {
                 }
             }
 
+            let transport = transport_for_id(&id);
             devices.push(DeviceInfo {
                 id,
                 status,
                 model,
                 product,
+                transport,
             });
         }
 
         devices
     }
 
+    /// Fetch several getprop values in a single `shell:` round-trip over the native ADB
+    /// protocol, keyed by property name. Returns `None` if the local ADB server can't be
+    /// reached over TCP (e.g. bundled adb only, no server running yet).
+    fn fetch_props_batched(
+        device_id: &str,
+        props: &[&str],
+    ) -> Option<std::collections::HashMap<String, String>> {
+        let mut client = AdbProtocolClient::connect_default().ok()?;
+        client
+            .set_timeout(std::time::Duration::from_secs(3))
+            .ok()?;
+
+        // Chain all getprop calls into one shell invocation, separated by a marker so we can
+        // split the output back into per-property values even if a prop is empty.
+        let command = props
+            .iter()
+            .map(|p| format!("getprop {}", p))
+            .collect::<Vec<_>>()
+            .join("; echo __ADBC_SEP__; ");
+
+        let output = client
+            .run_device_service(device_id, &format!("shell:{}", command))
+            .ok()?;
+        let text = String::from_utf8_lossy(&output);
+
+        let mut result = std::collections::HashMap::new();
+        for (prop, chunk) in props.iter().zip(text.split("__ADBC_SEP__")) {
+            result.insert(prop.to_string(), chunk.trim().to_string());
+        }
+
+        Some(result)
+    }
+
     /// Retrieve detailed model information using getprop.
+    /// Prefers a single batched round-trip over the native ADB protocol, falling back to one
+    /// process spawn per property when the local ADB server isn't reachable over TCP.
     fn get_device_model_info(&self, device_id: &str) -> Option<String> {
+        let props = ["ro.product.marketname", "ro.product.model", "ro.product.brand"];
+
+        let batched = Self::fetch_props_batched(device_id, &props).unwrap_or_default();
+
         let fetch_prop = |prop: &str| -> Option<String> {
+            if let Some(value) = batched.get(prop).filter(|s| !s.is_empty()) {
+                return Some(value.clone());
+            }
+
             let builder = AdbCommandBuilder::new().target(device_id);
             let args = builder.build(AdbCommand::GetProp(prop.to_string()));
             let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
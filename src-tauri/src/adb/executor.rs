@@ -15,6 +15,8 @@ pub enum DeviceStatus {
     Device,       // Connected and authorized
     Offline,      // Connected but not responding
     Unauthorized, // Connected but not authorized for debugging
+    Recovery,     // Booted into recovery
+    Sideload,     // In recovery, ready to receive a sideload package
     Unknown(String),
 }
 
@@ -24,11 +26,33 @@ impl From<&str> for DeviceStatus {
             "device" => DeviceStatus::Device,
             "offline" => DeviceStatus::Offline,
             "unauthorized" => DeviceStatus::Unauthorized,
+            "recovery" => DeviceStatus::Recovery,
+            "sideload" => DeviceStatus::Sideload,
             other => DeviceStatus::Unknown(other.to_string()),
         }
     }
 }
 
+/// Whether a device was discovered over USB or over a network (TCP/IP) transport. Network
+/// devices show up in `adb devices` with an `ip:port` id, so this is derived from the id's
+/// shape rather than tracked separately.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum DeviceTransport {
+    Usb,
+    Network,
+}
+
+/// A device id is a network transport's id (`host:port`, e.g. `192.168.1.5:5555`) if it
+/// contains a colon followed entirely by digits.
+pub fn transport_for_id(id: &str) -> DeviceTransport {
+    match id.rsplit_once(':') {
+        Some((_, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+            DeviceTransport::Network
+        }
+        _ => DeviceTransport::Usb,
+    }
+}
+
 /// Information about a connected Android device
 #[derive(Debug, Clone, serde::Serialize, PartialEq)]
 pub struct DeviceInfo {
@@ -36,6 +60,7 @@ pub struct DeviceInfo {
     pub status: DeviceStatus,
     pub model: Option<String>,
     pub product: Option<String>,
+    pub transport: DeviceTransport,
 }
 
 /// Executor for ADB commands
@@ -70,6 +95,12 @@ impl AdbExecutor {
         self.client.adb_path()
     }
 
+    /// Address of the ADB server this executor talks to directly over the wire protocol,
+    /// if it's using that backend instead of spawning the CLI per call.
+    pub fn server_addr(&self) -> Option<&str> {
+        self.client.server_addr()
+    }
+
     /// Check if using the bundled version of ADB.
     pub fn is_bundled(&self) -> bool {
         // Bundled path logic is now inside AdbClient::discover_adb
@@ -177,12 +208,58 @@ impl AdbExecutor {
         checker.check_action_requirements(device_id)
     }
 
+    /// Check APK/device install compatibility (legacy redirect).
+    pub fn check_apk_compatibility(
+        &self,
+        device_id: &str,
+        apk: &crate::apk::ApkInfo,
+    ) -> Vec<crate::requirements::RequirementCheck> {
+        let checker = crate::requirements::RequirementChecker::new(self);
+        checker.check_apk_compatibility(device_id, apk)
+    }
+
     /// Install an APK on a device (legacy redirect).
     pub fn install_apk(&self, device_id: &str, apk_path: &str) -> crate::apk::InstallResult {
         let installer = crate::apk::ApkInstaller::new(self);
         installer.install(device_id, apk_path)
     }
 
+    /// Install an APK on a device, targeting the given storage volume (legacy redirect).
+    pub fn install_apk_with_storage(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        storage: crate::apk::AndroidStorageInput,
+    ) -> crate::apk::InstallResult {
+        let installer = crate::apk::ApkInstaller::new(self);
+        installer.install_with_storage(device_id, apk_path, storage)
+    }
+
+    /// Install a split APK set (app bundle base + config splits) on a device, targeting the
+    /// given storage volume (legacy redirect).
+    pub fn install_apk_multiple(
+        &self,
+        device_id: &str,
+        apk_paths: &[&str],
+        storage: crate::apk::AndroidStorageInput,
+    ) -> crate::apk::InstallResult {
+        let installer = crate::apk::ApkInstaller::new(self);
+        installer.install_multiple_with_storage(device_id, apk_paths, storage)
+    }
+
+    /// Install an APK by pushing it over the native sync protocol and running `pm install`,
+    /// reporting byte-level progress (legacy redirect).
+    pub fn install_apk_streamed_push(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        storage: crate::apk::AndroidStorageInput,
+        on_progress: Option<&mut crate::adb::sync::ProgressCallback>,
+    ) -> crate::apk::InstallResult {
+        let installer = crate::apk::ApkInstaller::new(self);
+        installer.install_streamed_push(device_id, apk_path, storage, on_progress)
+    }
+
     // Exposed for legacy module use (like apk.rs and requirements.rs during transition)
     pub fn run_with_retry<F>(
         &self,
@@ -1,16 +1,30 @@
 // ADB Module - Handles all ADB interactions
 // This module provides a safe wrapper around the adb command-line tool
 
+pub mod bootstrap;
 pub mod client;
 pub mod command_builder;
 pub mod discovery;
 pub mod executor;
+pub mod protocol;
+pub mod recovery_flash;
+pub mod sideload;
+pub mod storage;
+pub mod sync;
 pub mod tracker;
 pub mod agent_manager;
+pub mod usb_monitor;
+pub mod wipe;
 
+pub use bootstrap::{ensure_adb, BootstrapProgress, BootstrapStage};
 pub use client::AdbClient;
 pub use command_builder::{AdbCommand, AdbCommandBuilder, ShellCommandBuilder};
 pub use discovery::AdbDiscovery;
-pub use executor::{AdbExecutor, DeviceInfo, DeviceStatus};
+pub use executor::{AdbExecutor, DeviceInfo, DeviceStatus, DeviceTransport};
+pub use protocol::AdbProtocolClient;
+pub use storage::AndroidStorage;
+pub use sync::{SyncClient, SyncCompression, SyncDirEntry};
 pub use tracker::start_device_tracker;
 pub use agent_manager::AgentManager;
+pub use usb_monitor::DeviceMonitorState;
+pub use wipe::{factory_reset, WipeMethod, WipeOptions, WipeOutcome};
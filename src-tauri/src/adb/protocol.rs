@@ -0,0 +1,227 @@
+// ADB Wire Protocol - Native host protocol client over TCP
+// Speaks directly to the ADB server (127.0.0.1:5037) instead of spawning the `adb` binary.
+// Wire format: every request is an ASCII service string prefixed by a 4-character hex length
+// (e.g. "000Chost:version"). The server replies with a 4-byte OKAY/FAIL status, and on FAIL a
+// 4-hex-length-prefixed UTF-8 error string follows.
+
+use crate::error::{AdbError, AppError};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Default address of the local ADB server.
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:5037";
+
+/// Encode a service request: 4 hex-digit length prefix followed by the payload.
+pub fn encode_message(service: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(service.len() + 4);
+    out.extend_from_slice(format!("{:04x}", service.len()).as_bytes());
+    out.extend_from_slice(service.as_bytes());
+    out
+}
+
+/// Read a 4-character hex ASCII length prefix from the stream.
+pub fn read_length(stream: &mut TcpStream) -> Result<usize, AppError> {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf).map_err(|e| {
+        AppError::from(AdbError::ExecutionFailed(format!(
+            "Failed to read length prefix: {}",
+            e
+        )))
+    })?;
+
+    let text = std::str::from_utf8(&buf).map_err(|_| {
+        AppError::from(AdbError::ParseError(
+            "Length prefix was not valid ASCII".into(),
+        ))
+    })?;
+
+    usize::from_str_radix(text, 16).map_err(|_| {
+        AppError::from(AdbError::ParseError(format!(
+            "Invalid hex length prefix: {}",
+            text
+        )))
+    })
+}
+
+/// A single connection to the ADB server speaking the host protocol.
+pub struct AdbProtocolClient {
+    stream: TcpStream,
+}
+
+impl AdbProtocolClient {
+    /// Connect to the ADB server at the given address.
+    pub fn connect(addr: &str) -> Result<Self, AppError> {
+        let stream = TcpStream::connect(addr).map_err(|e| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Failed to connect to adb server at {}: {}",
+                addr, e
+            )))
+        })?;
+        stream.set_nodelay(true).ok();
+        Ok(Self { stream })
+    }
+
+    /// Connect to the ADB server's default address (127.0.0.1:5037).
+    pub fn connect_default() -> Result<Self, AppError> {
+        Self::connect(DEFAULT_SERVER_ADDR)
+    }
+
+    /// Set read/write timeouts on the underlying socket.
+    pub fn set_timeout(&self, timeout: Duration) -> Result<(), AppError> {
+        self.stream.set_read_timeout(Some(timeout)).ok();
+        self.stream.set_write_timeout(Some(timeout)).ok();
+        Ok(())
+    }
+
+    /// Send a service request and wait for the OKAY/FAIL status.
+    /// On FAIL, reads the length-prefixed error message and returns it as an error.
+    pub fn send_request(&mut self, service: &str) -> Result<(), AppError> {
+        self.stream
+            .write_all(&encode_message(service))
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("Write failed: {}", e))))?;
+
+        self.read_status()
+    }
+
+    /// Read a 4-byte OKAY/FAIL status, consuming the error payload on FAIL.
+    pub fn read_status(&mut self) -> Result<(), AppError> {
+        let mut status = [0u8; 4];
+        self.stream.read_exact(&mut status).map_err(|e| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Failed to read status: {}",
+                e
+            )))
+        })?;
+
+        match &status {
+            b"OKAY" => Ok(()),
+            b"FAIL" => {
+                let len = read_length(&mut self.stream)?;
+                let mut message = vec![0u8; len];
+                self.stream.read_exact(&mut message).map_err(|e| {
+                    AppError::from(AdbError::ExecutionFailed(format!(
+                        "Failed to read FAIL message: {}",
+                        e
+                    )))
+                })?;
+                Err(AppError::from(AdbError::ExecutionFailed(
+                    String::from_utf8_lossy(&message).to_string(),
+                )))
+            }
+            other => Err(AppError::from(AdbError::ParseError(format!(
+                "Unexpected status bytes: {:?}",
+                other
+            )))),
+        }
+    }
+
+    /// Switch this connection to target a specific device, via `host:transport:<serial>`.
+    pub fn transport(&mut self, serial: &str) -> Result<(), AppError> {
+        self.send_request(&format!("host:transport:{}", serial))
+    }
+
+    /// Read the response to a service already sent on this connection (e.g. `shell:...` after
+    /// a successful transport) until EOF.
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, AppError> {
+        let mut buf = Vec::new();
+        self.stream.read_to_end(&mut buf).map_err(|e| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Failed to read response: {}",
+                e
+            )))
+        })?;
+        Ok(buf)
+    }
+
+    /// Run a device-scoped service and return its full streamed response.
+    /// `service` is a ready protocol service string (see `command_to_service` for mapping an
+    /// `AdbCommand` onto one).
+    pub fn run_device_service(&mut self, serial: &str, service: &str) -> Result<Vec<u8>, AppError> {
+        self.transport(serial)?;
+        self.send_request(service)?;
+        self.read_to_end()
+    }
+
+    /// Run a shell command via `exec:`, the non-pty counterpart to `shell:` (what `adb exec-out`
+    /// uses under the hood). Unlike `shell:`, stdout isn't munged for a terminal, so this is the
+    /// right service for binary output like `screencap -p`.
+    pub fn exec_out(&mut self, serial: &str, command: &str) -> Result<Vec<u8>, AppError> {
+        self.run_device_service(serial, &format!("exec:{}", command))
+    }
+
+    /// Access the underlying stream directly (e.g. to hand off to the sync protocol).
+    pub fn into_inner(self) -> TcpStream {
+        self.stream
+    }
+
+    pub fn stream_mut(&mut self) -> &mut TcpStream {
+        &mut self.stream
+    }
+}
+
+/// Map an `AdbCommand` onto the equivalent host-protocol service string, where possible.
+/// Commands that have no direct device-scoped service equivalent (e.g. `StartServer`) return `None`.
+pub fn command_to_service(command: &crate::adb::command_builder::AdbCommand) -> Option<String> {
+    use crate::adb::command_builder::AdbCommand;
+
+    match command {
+        AdbCommand::Shell(args) => Some(format!("shell:{}", args.join(" "))),
+        AdbCommand::GetProp(prop) => Some(format!("shell:getprop {}", prop)),
+        AdbCommand::Reboot { mode } => Some(format!(
+            "reboot:{}",
+            mode.clone().unwrap_or_default()
+        )),
+        _ => None,
+    }
+}
+
+/// Run a single `host:version`/`host:...` request against the default server and return the
+/// streamed reply. Used for simple one-shot queries that don't need a device transport.
+pub fn host_request(service: &str) -> Result<Vec<u8>, AppError> {
+    host_request_at(DEFAULT_SERVER_ADDR, service)
+}
+
+/// Same as `host_request`, against an explicit server address rather than the default.
+pub fn host_request_at(addr: &str, service: &str) -> Result<Vec<u8>, AppError> {
+    let mut client = AdbProtocolClient::connect(addr)?;
+    client.set_timeout(Duration::from_secs(5))?;
+    client.send_request(service)?;
+    client.read_to_end()
+}
+
+/// Query a device's feature set via `host-serial:<serial>:features` (what `adb features`
+/// uses under the hood). The server replies with a comma-separated list, e.g.
+/// `cmd,abb,abb_exec,fixed_push_mkdir`.
+pub fn device_features(serial: &str) -> Result<Vec<String>, AppError> {
+    let mut client = AdbProtocolClient::connect_default()?;
+    client.set_timeout(Duration::from_secs(5))?;
+    client.send_request(&format!("host-serial:{}:features", serial))?;
+    let output = client.read_to_end()?;
+    let text = String::from_utf8_lossy(&output);
+
+    Ok(text
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_message() {
+        let encoded = encode_message("host:version");
+        assert_eq!(&encoded[..4], b"000c");
+        assert_eq!(&encoded[4..], b"host:version");
+    }
+
+    #[test]
+    fn test_encode_message_length_is_hex() {
+        let service = "a".repeat(300);
+        let encoded = encode_message(&service);
+        assert_eq!(&encoded[..4], b"012c");
+    }
+}
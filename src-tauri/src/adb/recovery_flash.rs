@@ -0,0 +1,102 @@
+// Recovery Flash - Helpers for driving the full OTA/recovery-ZIP sideload flow: waiting for a
+// device to re-enumerate in `sideload` mode after a reboot, and making sense of the progress
+// and error text recovery prints while `adb sideload` streams the package to it.
+//
+// Recovery has shipped two back-ends for this over the years: the classic block-serving
+// protocol (`serving: '<path>'  (~NN%)` lines) and the newer FUSE-based sideload host, which
+// just prints a bare `NN%`. Both are handled by stripping everything but the trailing
+// percentage.
+
+use crate::adb::executor::{AdbExecutor, DeviceStatus};
+use crate::error::AppError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for a device to come back up in `sideload` mode after rebooting into
+/// recovery before giving up.
+pub const SIDELOAD_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Poll `adb devices` until `device_id` reports `DeviceStatus::Sideload`, or time out.
+pub fn wait_for_sideload_mode(device_id: &str, timeout: Duration) -> Result<(), AppError> {
+    let executor = AdbExecutor::new();
+    let deadline = Instant::now() + timeout;
+
+    while Instant::now() < deadline {
+        if let Ok(devices) = executor.list_devices() {
+            if devices
+                .iter()
+                .any(|d| d.id == device_id && d.status == DeviceStatus::Sideload)
+            {
+                return Ok(());
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    Err(AppError::new(
+        "SIDELOAD_WAIT_TIMEOUT",
+        "Timed out waiting for the device to enter sideload mode. Confirm it rebooted into recovery and select \"Apply update from ADB\" (or equivalent).",
+    ))
+}
+
+/// Parse a progress percentage out of a line recovery printed while serving the package,
+/// whichever of the two back-ends produced it (`serving: 'x.zip'  (~45%)` or a bare `45%`).
+/// Returns `None` for lines with no trailing percentage (banners, the final summary, etc).
+pub fn parse_progress_percent(line: &str) -> Option<u8> {
+    let line = line.trim();
+    let percent_pos = line.rfind('%')?;
+    let digits_end = percent_pos;
+    let digits_start = line[..digits_end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    if digits_start == digits_end {
+        return None;
+    }
+
+    line[digits_start..digits_end].parse::<u8>().ok()
+}
+
+/// Whether a line of `adb sideload` output indicates recovery rejected the package because its
+/// signature (or the device's `otacerts` trust) didn't verify.
+pub fn is_signature_failure(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.contains("signature verification failed") || lower.contains("footer is wrong")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_percent_classic_backend() {
+        assert_eq!(
+            parse_progress_percent("serving: 'update.zip'  (~45%)    "),
+            Some(45)
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_percent_fuse_backend() {
+        assert_eq!(parse_progress_percent("12%"), Some(12));
+    }
+
+    #[test]
+    fn test_parse_progress_percent_no_percentage() {
+        assert_eq!(parse_progress_percent("Total xfer: 1.00x"), None);
+    }
+
+    #[test]
+    fn test_is_signature_failure_detects_classic_message() {
+        assert!(is_signature_failure(
+            "E:Signature verification failed"
+        ));
+    }
+
+    #[test]
+    fn test_is_signature_failure_ignores_progress_lines() {
+        assert!(!is_signature_failure("serving: 'update.zip'  (~45%)"));
+    }
+}
@@ -0,0 +1,169 @@
+// Android Storage Resolution - Maps a logical storage location to an absolute device path
+// Lets push/pull targets avoid hard-coding `/sdcard`, since the external storage mount point
+// varies across devices (and some expose no external storage at all).
+
+use crate::adb::protocol::AdbProtocolClient;
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Fallback external storage root used when `$EXTERNAL_STORAGE` can't be read.
+const DEFAULT_SDCARD_ROOT: &str = "/sdcard";
+
+/// Scratch directory this app stages its own file transfers under, regardless of device. Used
+/// as the `App` fallback when `run-as` isn't available (the companion app isn't installed, or
+/// isn't debuggable).
+const APP_ROOT: &str = "/data/local/tmp/adb-compass";
+
+/// Package id of the companion app whose private directory `App` resolves to via `run-as`.
+const APP_PACKAGE_ID: &str = "com.h1dr0n.adbcompass";
+
+lazy_static::lazy_static! {
+    /// First writable root `Auto` found for a given device serial, so repeated resolutions don't
+    /// re-probe candidates that already succeeded (or failed) once this session.
+    static ref WRITABLE_ROOT_CACHE: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Logical storage location to resolve push/pull paths against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AndroidStorage {
+    /// Probe `Internal`, `Sdcard`, and `App` in order and use the first writable one, caching
+    /// the result per device serial.
+    #[default]
+    Auto,
+    /// This app's private data directory, via `run-as` (falls back to a fixed scratch directory
+    /// under `/data/local/tmp` if the companion app isn't installed/debuggable on the device).
+    App,
+    /// Internal storage accessible without root (`/data/local/tmp`).
+    Internal,
+    /// The device's external storage mount point, via `$EXTERNAL_STORAGE` (falling back to the
+    /// conventional `/sdcard` path if that's unset).
+    Sdcard,
+}
+
+impl AndroidStorage {
+    /// Resolve this storage location to an absolute root path on `device_id`.
+    pub fn resolve_root(&self, device_id: &str) -> Result<String, AppError> {
+        match self {
+            AndroidStorage::Internal => Ok("/data/local/tmp".to_string()),
+            AndroidStorage::Sdcard => Ok(Self::resolve_sdcard_root(device_id)),
+            AndroidStorage::App => Ok(Self::resolve_app_root(device_id)),
+            AndroidStorage::Auto => Self::resolve_auto_root(device_id),
+        }
+    }
+
+    /// Resolve `relative` against this storage location's root. An already-absolute path is
+    /// returned unchanged, so callers can keep passing full paths without a behavior change.
+    pub fn resolve_path(&self, device_id: &str, relative: &str) -> Result<String, AppError> {
+        if relative.starts_with('/') {
+            return Ok(relative.to_string());
+        }
+
+        let root = self.resolve_root(device_id)?;
+        Ok(format!("{}/{}", root.trim_end_matches('/'), relative))
+    }
+
+    fn resolve_sdcard_root(device_id: &str) -> String {
+        AdbProtocolClient::connect_default()
+            .and_then(|mut client| {
+                client.run_device_service(device_id, "shell:echo $EXTERNAL_STORAGE")
+            })
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output).trim().to_string())
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| DEFAULT_SDCARD_ROOT.to_string())
+    }
+
+    fn resolve_app_root(device_id: &str) -> String {
+        AdbProtocolClient::connect_default()
+            .and_then(|mut client| {
+                client.run_device_service(device_id, &format!("shell:run-as {} pwd", APP_PACKAGE_ID))
+            })
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output).trim().to_string())
+            .filter(|path| path.starts_with('/'))
+            .unwrap_or_else(|| APP_ROOT.to_string())
+    }
+
+    /// Probe `Internal`, `Sdcard`, and `App` roots in order and cache the first one `device_id`
+    /// can actually write to, so callers that don't care which storage they land on get a root
+    /// that works without the caller having to know device-specific quirks.
+    fn resolve_auto_root(device_id: &str) -> Result<String, AppError> {
+        if let Some(cached) = WRITABLE_ROOT_CACHE.lock().unwrap().get(device_id) {
+            return Ok(cached.clone());
+        }
+
+        for candidate in [
+            AndroidStorage::Internal,
+            AndroidStorage::Sdcard,
+            AndroidStorage::App,
+        ] {
+            let root = candidate.resolve_root(device_id)?;
+            if Self::probe_writable(device_id, &root) {
+                WRITABLE_ROOT_CACHE
+                    .lock()
+                    .unwrap()
+                    .insert(device_id.to_string(), root.clone());
+                return Ok(root);
+            }
+        }
+
+        // Nothing probed writable (e.g. no reachable server) - fall back to the external storage
+        // mount point rather than failing outright.
+        Ok(Self::resolve_sdcard_root(device_id))
+    }
+
+    /// Check whether `root` is writable on `device_id` by touching and removing a marker file.
+    fn probe_writable(device_id: &str, root: &str) -> bool {
+        let probe_path = format!("{}/.adbcompass_write_test", root.trim_end_matches('/'));
+        let command = format!(
+            "shell:touch {} 2>/dev/null && rm -f {} && echo OK_WRITE || echo NO_WRITE",
+            probe_path, probe_path
+        );
+
+        AdbProtocolClient::connect_default()
+            .and_then(|mut client| client.run_device_service(device_id, &command))
+            .map(|output| String::from_utf8_lossy(&output).contains("OK_WRITE"))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path_passes_through_absolute_paths() {
+        // Doesn't need a real device connection since absolute paths skip resolution.
+        assert_eq!(
+            AndroidStorage::Sdcard
+                .resolve_path("emulator-5554", "/data/local/tmp/app.apk")
+                .unwrap(),
+            "/data/local/tmp/app.apk"
+        );
+    }
+
+    #[test]
+    fn test_fixed_roots() {
+        // With no reachable adb server, Sdcard/App fall back to their fixed defaults.
+        assert_eq!(
+            AndroidStorage::Internal.resolve_root("any").unwrap(),
+            "/data/local/tmp"
+        );
+        assert_eq!(
+            AndroidStorage::Sdcard.resolve_root("any").unwrap(),
+            "/sdcard"
+        );
+        assert_eq!(AndroidStorage::App.resolve_root("any").unwrap(), APP_ROOT);
+    }
+
+    #[test]
+    fn test_auto_falls_back_without_a_reachable_server() {
+        assert_eq!(
+            AndroidStorage::Auto.resolve_root("no-such-device").unwrap(),
+            "/sdcard"
+        );
+    }
+}
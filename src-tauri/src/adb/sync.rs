@@ -0,0 +1,516 @@
+// ADB Sync Protocol - Native push/pull over the `sync:` service
+// Implements the 8-byte-header sync sub-protocol (SEND/RECV/STAT/DATA/DONE/OKAY/FAIL) used by
+// `adb push`/`adb pull`, so file transfers don't need to shell out and can report progress.
+
+use crate::adb::command_builder::quote_shell_arg;
+use crate::adb::protocol::AdbProtocolClient;
+use crate::error::{AdbError, AppError};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Maximum size of a single sync DATA chunk, per the protocol.
+const MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Maximum length of a `SEND`/`RECV`/`STAT`/`LIST` path argument (adbd rejects anything longer).
+const MAX_PATH_LEN: usize = 1024;
+
+/// Default Unix file mode applied to pushed regular files.
+pub(crate) const DEFAULT_PUSH_MODE: u32 = 0o644;
+
+/// Characters a remote path is allowed to contain. The sync `SEND` header has no quoting of
+/// its own (unlike a `shell:` command line), so anything outside this set is rejected instead
+/// of being escaped.
+const SAFE_PATH_CHARS: &str = "_@%+=:,./-";
+
+/// Reject remote paths long enough to risk overflowing the protocol's length limit. Applied to
+/// every sync sub-command, since a `u32` length prefix technically allows far more than adbd
+/// will actually accept.
+fn validate_path_len(remote_path: &str) -> Result<(), AppError> {
+    if !remote_path.is_empty() && remote_path.len() <= MAX_PATH_LEN {
+        Ok(())
+    } else {
+        Err(AppError::from(AdbError::ParseError(format!(
+            "Remote path is empty or exceeds the {}-byte protocol limit: {}",
+            MAX_PATH_LEN, remote_path
+        ))))
+    }
+}
+
+/// Reject remote paths containing characters outside `[A-Za-z0-9_@%+=:,./-]`, since the sync
+/// `SEND` header packs `<remotepath>,<mode>` with no escaping mechanism. Only applies to
+/// `SEND` (push), which is the one sub-command whose header is ambiguous with unsafe chars;
+/// `STAT`/`LIST`/`RECV` only need the length check above.
+fn validate_remote_path(remote_path: &str) -> Result<(), AppError> {
+    validate_path_len(remote_path)?;
+
+    let is_safe = remote_path
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || SAFE_PATH_CHARS.contains(c));
+
+    if is_safe {
+        Ok(())
+    } else {
+        Err(AppError::from(AdbError::ParseError(format!(
+            "Unsafe remote path: {}",
+            remote_path
+        ))))
+    }
+}
+
+/// Progress callback: (bytes_transferred, total_bytes, current_path)
+pub type ProgressCallback<'a> = dyn FnMut(u64, u64, &str) + 'a;
+
+/// Compression codec applied to a single sync transfer, as requested by the caller. `DATA` chunks are compressed
+/// independently (each chunk is its own LZ4 frame) so progress can still be reported per chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncCompression {
+    None,
+    Lz4,
+}
+
+fn compress_chunk(data: &[u8], codec: SyncCompression) -> Vec<u8> {
+    match codec {
+        SyncCompression::None => data.to_vec(),
+        SyncCompression::Lz4 => lz4_flex::compress_prepend_size(data),
+    }
+}
+
+fn decompress_chunk(data: &[u8], codec: SyncCompression) -> Result<Vec<u8>, AppError> {
+    match codec {
+        SyncCompression::None => Ok(data.to_vec()),
+        SyncCompression::Lz4 => lz4_flex::decompress_size_prepended(data).map_err(|e| {
+            AppError::from(AdbError::ParseError(format!(
+                "Failed to decompress sync chunk: {}",
+                e
+            )))
+        }),
+    }
+}
+
+/// Metadata returned by the sync `STAT` command.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncStat {
+    pub mode: u32,
+    pub size: u32,
+    pub mtime: u32,
+}
+
+impl SyncStat {
+    pub fn exists(&self) -> bool {
+        self.mode != 0
+    }
+
+    pub fn is_directory(&self) -> bool {
+        // S_IFDIR = 0o040000
+        (self.mode & 0o170000) == 0o040000
+    }
+}
+
+/// A single entry returned by the sync `LIST` command.
+#[derive(Debug, Clone)]
+pub struct SyncDirEntry {
+    pub name: String,
+    pub mode: u32,
+    pub size: u32,
+    pub mtime: u32,
+}
+
+impl SyncDirEntry {
+    pub fn is_directory(&self) -> bool {
+        // S_IFDIR = 0o040000
+        (self.mode & 0o170000) == 0o040000
+    }
+}
+
+/// A connection to a device's `sync:` service.
+pub struct SyncClient {
+    protocol: AdbProtocolClient,
+    serial: String,
+}
+
+impl SyncClient {
+    /// Open a sync session against the given device serial.
+    pub fn connect(serial: &str) -> Result<Self, AppError> {
+        let mut protocol = AdbProtocolClient::connect_default()?;
+        protocol.transport(serial)?;
+        protocol.send_request("sync:")?;
+        Ok(Self {
+            protocol,
+            serial: serial.to_string(),
+        })
+    }
+
+    fn write_id_len(&mut self, id: &[u8; 4], len: u32) -> Result<(), AppError> {
+        let mut buf = Vec::with_capacity(8);
+        buf.extend_from_slice(id);
+        buf.extend_from_slice(&len.to_le_bytes());
+        self.protocol
+            .stream_mut()
+            .write_all(&buf)
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write: {}", e))))
+    }
+
+    fn read_id(&mut self) -> Result<[u8; 4], AppError> {
+        let mut id = [0u8; 4];
+        self.protocol
+            .stream_mut()
+            .read_exact(&mut id)
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync read id: {}", e))))?;
+        Ok(id)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, AppError> {
+        let mut buf = [0u8; 4];
+        self.protocol
+            .stream_mut()
+            .read_exact(&mut buf)
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync read len: {}", e))))?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    /// Issue a `STAT` request for a remote path.
+    pub fn stat(&mut self, remote_path: &str) -> Result<SyncStat, AppError> {
+        validate_path_len(remote_path)?;
+        self.write_id_len(b"STAT", remote_path.len() as u32)?;
+        self.protocol
+            .stream_mut()
+            .write_all(remote_path.as_bytes())
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write path: {}", e))))?;
+
+        let id = self.read_id()?;
+        if &id != b"STAT" {
+            return Err(AppError::from(AdbError::ParseError(format!(
+                "Expected STAT reply, got {:?}",
+                id
+            ))));
+        }
+
+        let mode = self.read_u32()?;
+        let size = self.read_u32()?;
+        let mtime = self.read_u32()?;
+
+        Ok(SyncStat { mode, size, mtime })
+    }
+
+    /// List the contents of a remote directory via `LIST`, reading streamed `DENT` entries
+    /// until the server sends `DONE`.
+    pub fn list(&mut self, remote_path: &str) -> Result<Vec<SyncDirEntry>, AppError> {
+        validate_path_len(remote_path)?;
+        self.write_id_len(b"LIST", remote_path.len() as u32)?;
+        self.protocol
+            .stream_mut()
+            .write_all(remote_path.as_bytes())
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write path: {}", e))))?;
+
+        let mut entries = Vec::new();
+
+        loop {
+            let id = self.read_id()?;
+            match &id {
+                b"DENT" => {
+                    let mode = self.read_u32()?;
+                    let size = self.read_u32()?;
+                    let mtime = self.read_u32()?;
+                    let namelen = self.read_u32()? as usize;
+                    if namelen > MAX_PATH_LEN {
+                        return Err(AppError::from(AdbError::ParseError(format!(
+                            "DENT name of {} bytes exceeds the {}-byte protocol limit",
+                            namelen, MAX_PATH_LEN
+                        ))));
+                    }
+
+                    let mut name_buf = vec![0u8; namelen];
+                    self.protocol
+                        .stream_mut()
+                        .read_exact(&mut name_buf)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                    let name = String::from_utf8_lossy(&name_buf).to_string();
+
+                    if name != "." && name != ".." {
+                        entries.push(SyncDirEntry {
+                            name,
+                            mode,
+                            size,
+                            mtime,
+                        });
+                    }
+                }
+                b"DONE" => {
+                    // DONE carries a zeroed dent-shaped payload (mode/size/mtime/namelen) to
+                    // keep the reply uniform; drain and discard it.
+                    let mut drain = [0u8; 16];
+                    self.protocol
+                        .stream_mut()
+                        .read_exact(&mut drain)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                    break;
+                }
+                b"FAIL" => {
+                    let len = self.read_u32()? as usize;
+                    let mut msg = vec![0u8; len];
+                    self.protocol
+                        .stream_mut()
+                        .read_exact(&mut msg)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                    return Err(AppError::from(AdbError::ExecutionFailed(
+                        String::from_utf8_lossy(&msg).to_string(),
+                    )));
+                }
+                other => {
+                    return Err(AppError::from(AdbError::ParseError(format!(
+                        "Unexpected sync reply: {:?}",
+                        other
+                    ))))
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Push a single local file to a remote path, calling `on_progress` after each chunk. Each
+    /// `DATA` chunk is compressed independently per `compression`; returns the codec actually
+    /// used so the caller can report it even though (for now) every request succeeds as asked.
+    pub fn push_file(
+        &mut self,
+        local_path: &Path,
+        remote_path: &str,
+        mode: u32,
+        compression: SyncCompression,
+        on_progress: Option<&mut ProgressCallback>,
+    ) -> Result<SyncCompression, AppError> {
+        validate_remote_path(remote_path)?;
+
+        let mut file = File::open(local_path).map_err(|e| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Failed to open {}: {}",
+                local_path.display(),
+                e
+            )))
+        })?;
+
+        let total = file
+            .metadata()
+            .map(|m| m.len())
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+
+        let header = format!("{},{}", remote_path, mode);
+        self.write_id_len(b"SEND", header.len() as u32)?;
+        self.protocol
+            .stream_mut()
+            .write_all(header.as_bytes())
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write header: {}", e))))?;
+
+        let mut sent: u64 = 0;
+        let mut buf = vec![0u8; MAX_CHUNK_SIZE];
+        let mut on_progress = on_progress;
+
+        loop {
+            let n = file
+                .read(&mut buf)
+                .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+            if n == 0 {
+                break;
+            }
+
+            let payload = compress_chunk(&buf[..n], compression);
+            self.write_id_len(b"DATA", payload.len() as u32)?;
+            self.protocol
+                .stream_mut()
+                .write_all(&payload)
+                .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write data: {}", e))))?;
+
+            sent += n as u64;
+            if let Some(cb) = on_progress.as_mut() {
+                cb(sent, total, remote_path);
+            }
+        }
+
+        let mtime = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as u32)
+            .unwrap_or(0);
+        self.write_id_len(b"DONE", mtime)?;
+
+        let id = self.read_id()?;
+        match &id {
+            b"OKAY" => Ok(compression),
+            b"FAIL" => {
+                let len = self.read_u32()? as usize;
+                let mut msg = vec![0u8; len];
+                self.protocol
+                    .stream_mut()
+                    .read_exact(&mut msg)
+                    .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                Err(AppError::from(AdbError::ExecutionFailed(
+                    String::from_utf8_lossy(&msg).to_string(),
+                )))
+            }
+            other => Err(AppError::from(AdbError::ParseError(format!(
+                "Unexpected sync reply: {:?}",
+                other
+            )))),
+        }
+    }
+
+    /// Pull a single remote file to a local path, calling `on_progress` after each chunk. Each
+    /// `DATA` chunk is decompressed per `compression`; returns the codec actually used.
+    pub fn pull_file(
+        &mut self,
+        remote_path: &str,
+        local_path: &Path,
+        compression: SyncCompression,
+        on_progress: Option<&mut ProgressCallback>,
+    ) -> Result<SyncCompression, AppError> {
+        let total = self.stat(remote_path)?.size as u64;
+
+        self.write_id_len(b"RECV", remote_path.len() as u32)?;
+        self.protocol
+            .stream_mut()
+            .write_all(remote_path.as_bytes())
+            .map_err(|e| AppError::from(AdbError::ExecutionFailed(format!("sync write path: {}", e))))?;
+
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+
+        let mut out = File::create(local_path).map_err(|e| {
+            AppError::from(AdbError::ExecutionFailed(format!(
+                "Failed to create {}: {}",
+                local_path.display(),
+                e
+            )))
+        })?;
+
+        let mut received: u64 = 0;
+        let mut on_progress = on_progress;
+
+        loop {
+            let id = self.read_id()?;
+            match &id {
+                b"DATA" => {
+                    let len = self.read_u32()? as usize;
+                    if len > MAX_CHUNK_SIZE {
+                        return Err(AppError::from(AdbError::ParseError(format!(
+                            "DATA chunk of {} bytes exceeds the {}-byte protocol limit",
+                            len, MAX_CHUNK_SIZE
+                        ))));
+                    }
+                    let mut chunk = vec![0u8; len];
+                    self.protocol
+                        .stream_mut()
+                        .read_exact(&mut chunk)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                    let chunk = decompress_chunk(&chunk, compression)?;
+                    out.write_all(&chunk)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+
+                    received += chunk.len() as u64;
+                    if let Some(cb) = on_progress.as_mut() {
+                        cb(received, total, remote_path);
+                    }
+                }
+                b"DONE" => {
+                    let _mtime = self.read_u32()?;
+                    break;
+                }
+                b"FAIL" => {
+                    let len = self.read_u32()? as usize;
+                    let mut msg = vec![0u8; len];
+                    self.protocol
+                        .stream_mut()
+                        .read_exact(&mut msg)
+                        .map_err(|e| AppError::from(AdbError::ExecutionFailed(e.to_string())))?;
+                    return Err(AppError::from(AdbError::ExecutionFailed(
+                        String::from_utf8_lossy(&msg).to_string(),
+                    )));
+                }
+                other => {
+                    return Err(AppError::from(AdbError::ParseError(format!(
+                        "Unexpected sync reply: {:?}",
+                        other
+                    ))))
+                }
+            }
+        }
+
+        Ok(compression)
+    }
+
+    /// Create a remote directory via a shell command on a separate connection, since this
+    /// connection is already pinned to the `sync:` service.
+    pub(crate) fn mkdir_remote(&mut self, remote_path: &str) -> Result<(), AppError> {
+        let mut client = AdbProtocolClient::connect_default()?;
+        client.run_device_service(
+            &self.serial,
+            &format!("shell:mkdir -p {}", quote_shell_arg(remote_path)),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_stat_is_directory() {
+        let stat = SyncStat {
+            mode: 0o040755,
+            size: 0,
+            mtime: 0,
+        };
+        assert!(stat.is_directory());
+    }
+
+    #[test]
+    fn test_sync_stat_not_found() {
+        let stat = SyncStat {
+            mode: 0,
+            size: 0,
+            mtime: 0,
+        };
+        assert!(!stat.exists());
+    }
+
+    #[test]
+    fn test_validate_remote_path_accepts_safe_chars() {
+        assert!(validate_remote_path("/data/local/tmp/app.apk").is_ok());
+    }
+
+    #[test]
+    fn test_validate_remote_path_rejects_shell_metacharacters() {
+        assert!(validate_remote_path("/data/local/tmp/$(reboot)").is_err());
+        assert!(validate_remote_path("/data/local/tmp/a; rm -rf /").is_err());
+    }
+
+    #[test]
+    fn test_sync_dir_entry_is_directory() {
+        let entry = SyncDirEntry {
+            name: "sdcard".to_string(),
+            mode: 0o040755,
+            size: 0,
+            mtime: 0,
+        };
+        assert!(entry.is_directory());
+    }
+
+    #[test]
+    fn test_validate_remote_path_rejects_oversized_path() {
+        let path = "/".to_string() + &"a".repeat(MAX_PATH_LEN);
+        assert!(validate_remote_path(&path).is_err());
+    }
+
+    #[test]
+    fn test_sync_dir_entry_is_not_directory() {
+        let entry = SyncDirEntry {
+            name: "app.apk".to_string(),
+            mode: 0o100644,
+            size: 1024,
+            mtime: 0,
+        };
+        assert!(!entry.is_directory());
+    }
+}
@@ -1,33 +1,51 @@
-// Device Tracker - Real-time device tracking using adb track-devices
-// Spawns adb track-devices as background process and emits events on device changes
+// Device Tracker - Real-time device tracking using the native host:track-devices service
+// Keeps a long-lived connection to the ADB server open and emits events as soon as the
+// server pushes a new device-list snapshot, instead of polling `adb track-devices` as a
+// subprocess.
 
-use std::io::{BufRead, BufReader};
-use std::process::Stdio;
+use std::collections::HashMap;
+use std::io::Read;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::adb::discovery::AdbDiscovery;
 use crate::adb::executor::{AdbExecutor, DeviceInfo, DeviceStatus};
-use crate::command_utils::hidden_command;
+use crate::adb::protocol::{read_length, AdbProtocolClient};
 
 /// Debounce delay to avoid rapid successive device list fetches
 const DEBOUNCE_MS: u64 = 500;
 
+/// Delay before retrying the `host:track-devices` connection after it drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
 /// Event payload for device changes
 #[derive(Clone, serde::Serialize)]
 pub struct DeviceChangedPayload {
     pub devices: Vec<DeviceInfo>,
 }
 
+/// Event payload for a single device's state transition (e.g. `unauthorized` -> `device`),
+/// derived directly from successive `host:track-devices` snapshots. `old_status` is `None` the
+/// first time a serial is seen this session.
+#[derive(Clone, serde::Serialize)]
+pub struct DeviceTransitionPayload {
+    pub serial: String,
+    pub old_status: Option<DeviceStatus>,
+    pub new_status: DeviceStatus,
+}
+
 /// Start the device tracker in a background thread
 pub fn start_device_tracker(app: AppHandle) {
     let running = Arc::new(AtomicBool::new(true));
     let last_devices = Arc::new(Mutex::new(Vec::<DeviceInfo>::new()));
+    let last_snapshot = Arc::new(Mutex::new(HashMap::<String, DeviceStatus>::new()));
 
     let running_clone = running.clone();
     let last_devices_clone = last_devices.clone();
+    let last_snapshot_clone = last_snapshot.clone();
     let app_handle = app.clone();
 
     // Store state for cleanup
@@ -35,7 +53,7 @@ pub fn start_device_tracker(app: AppHandle) {
 
     // Thread 1: Official ADB tracker (Events-driven)
     thread::spawn(move || {
-        run_tracker(app_handle, running_clone, last_devices_clone);
+        run_tracker(app_handle, running_clone, last_devices_clone, last_snapshot_clone);
     });
 }
 
@@ -64,12 +82,14 @@ fn run_tracker(
     app: AppHandle,
     running: Arc<AtomicBool>,
     last_devices: Arc<Mutex<Vec<DeviceInfo>>>,
+    last_snapshot: Arc<Mutex<HashMap<String, DeviceStatus>>>,
 ) {
     let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path().clone();
-    let mut last_emit_time = Instant::now() - Duration::from_secs(10);
 
-    // Spawn a subordinate heartbeat thread for this tracker session
+    // Spawn a subordinate heartbeat thread for this tracker session. The socket-based loop
+    // below reacts to connection-state changes the instant the server reports them, but a
+    // device can also transition between states (e.g. unauthorized -> device) without the
+    // server pushing a fresh line, so we keep polling those devices at a tighter interval.
     let app_heartbeat = app.clone();
     let running_heartbeat = running.clone();
     let last_devices_heartbeat = last_devices.clone();
@@ -103,52 +123,119 @@ fn run_tracker(
         }
     });
 
+    while running.load(Ordering::Relaxed) {
+        match track_devices_once(&app, &executor, &last_devices, &last_snapshot, &running) {
+            Ok(()) => {
+                // Connection closed cleanly (e.g. server restarted); reconnect immediately.
+            }
+            Err(e) => {
+                eprintln!("host:track-devices connection failed: {}", e);
+                thread::sleep(RECONNECT_DELAY);
+            }
+        }
+    }
+}
+
+/// Open a single `host:track-devices` connection and stream snapshots from it until it
+/// disconnects or the tracker is asked to stop.
+fn track_devices_once(
+    app: &AppHandle,
+    executor: &AdbExecutor,
+    last_devices: &Arc<Mutex<Vec<DeviceInfo>>>,
+    last_snapshot: &Arc<Mutex<HashMap<String, DeviceStatus>>>,
+    running: &Arc<AtomicBool>,
+) -> Result<(), crate::error::AppError> {
+    let mut client = AdbProtocolClient::connect_default()?;
+    client.send_request("host:track-devices")?;
+
+    let mut last_emit_time = Instant::now() - Duration::from_secs(10);
+
     loop {
         if !running.load(Ordering::Relaxed) {
-            break;
+            return Ok(());
         }
 
-        let child = hidden_command(&adb_path)
-            .arg("track-devices")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn();
-
-        match child {
-            Ok(mut child) => {
-                if let Some(stdout) = child.stdout.take() {
-                    let reader = BufReader::new(stdout);
-
-                    for line in reader.lines() {
-                        if !running.load(Ordering::Relaxed) {
-                            let _ = child.kill();
-                            break;
-                        }
-
-                        match line {
-                            Ok(text) => {
-                                if !text.trim().is_empty() {
-                                    let now = Instant::now();
-                                    if now.duration_since(last_emit_time)
-                                        >= Duration::from_millis(DEBOUNCE_MS)
-                                    {
-                                        emit_if_changed(&app, &executor, &last_devices);
-                                        last_emit_time = now;
-                                    }
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                }
-                if running.load(Ordering::Relaxed) {
-                    thread::sleep(Duration::from_secs(1));
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to start track-devices: {}", e);
-                thread::sleep(Duration::from_secs(5));
+        let len = match read_length(client.stream_mut()) {
+            Ok(len) => len,
+            Err(_) => return Ok(()), // server closed the connection; reconnect from the top
+        };
+
+        let mut buf = vec![0u8; len];
+        client.stream_mut().read_exact(&mut buf).map_err(|e| {
+            crate::error::AppError::from(crate::error::AdbError::ExecutionFailed(format!(
+                "Failed to read track-devices snapshot: {}",
+                e
+            )))
+        })?;
+
+        let snapshot = String::from_utf8_lossy(&buf);
+        let devices = AdbDiscovery::parse_devices_lines(snapshot.lines());
+
+        // Diff the parsed serial/state table against the previous snapshot so a transition
+        // (e.g. `unauthorized` -> `device`) can be reported precisely, instead of treating
+        // every pushed block as an opaque "something changed" signal and re-running
+        // `list_devices` regardless.
+        let any_changed = diff_snapshot(app, &devices, last_snapshot);
+
+        if any_changed {
+            let now = Instant::now();
+            if now.duration_since(last_emit_time) >= Duration::from_millis(DEBOUNCE_MS) {
+                // Only `list_devices` (which shells out for model/product enrichment) once a
+                // real change was detected, instead of on every snapshot the server pushes.
+                emit_if_changed(app, executor, last_devices);
+                last_emit_time = now;
             }
         }
     }
 }
+
+/// Compare a freshly-parsed serial/state table against `last_snapshot`, emitting a
+/// `device-transition` event for each serial that appeared, changed state, or disappeared, and
+/// updating `last_snapshot` in place. Returns whether anything changed at all.
+fn diff_snapshot(
+    app: &AppHandle,
+    devices: &[DeviceInfo],
+    last_snapshot: &Arc<Mutex<HashMap<String, DeviceStatus>>>,
+) -> bool {
+    let current: HashMap<String, DeviceStatus> = devices
+        .iter()
+        .map(|d| (d.id.clone(), d.status.clone()))
+        .collect();
+
+    let mut last = last_snapshot.lock().unwrap();
+    let mut any_changed = false;
+
+    for (serial, status) in &current {
+        let old_status = last.get(serial).cloned();
+        if old_status.as_ref() != Some(status) {
+            any_changed = true;
+            let _ = app.emit(
+                "device-transition",
+                DeviceTransitionPayload {
+                    serial: serial.clone(),
+                    old_status,
+                    new_status: status.clone(),
+                },
+            );
+        }
+    }
+
+    for (serial, status) in last.iter() {
+        if !current.contains_key(serial) {
+            any_changed = true;
+            // Synthesized, not an adb-reported status: the serial simply dropped out of the
+            // tracker's table (USB unplugged, emulator killed, etc).
+            let _ = app.emit(
+                "device-transition",
+                DeviceTransitionPayload {
+                    serial: serial.clone(),
+                    old_status: Some(status.clone()),
+                    new_status: DeviceStatus::Unknown("disconnected".to_string()),
+                },
+            );
+        }
+    }
+
+    *last = current;
+    any_changed
+}
@@ -0,0 +1,230 @@
+// USB Hotplug Device Monitor - Emits device-arrived/device-departed the instant a device is
+// plugged in or unplugged, instead of the frontend polling get_devices/refresh_devices.
+// Registers a libusb hotplug callback via `rusb` when the host supports it, and falls back to
+// diffing `list_devices` snapshots on a timer otherwise.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rusb::{Context, Device, HotplugBuilder, UsbContext};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::adb::executor::{AdbExecutor, DeviceInfo};
+use crate::commands::logcat::LogcatState;
+
+/// Delay between snapshots when falling back to polling (no hotplug support).
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A freshly-arrived USB device often isn't in adb's device list the instant libusb notices
+/// it, since the adb server needs a moment to complete its own enumeration. Retry the
+/// correlation a few times before giving up.
+const CORRELATE_RETRIES: u32 = 5;
+const CORRELATE_RETRY_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Clone, serde::Serialize)]
+pub struct DeviceArrivedPayload {
+    pub device: DeviceInfo,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct DeviceDepartedPayload {
+    pub serial: String,
+}
+
+/// Tauri-managed state for the USB monitor, mirroring `LogcatState`'s shape: a handle the
+/// frontend can start/stop via commands, kept alive in `app.manage(...)`.
+pub struct DeviceMonitorState {
+    running: Arc<AtomicBool>,
+}
+
+impl DeviceMonitorState {
+    pub fn new() -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl Default for DeviceMonitorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct HotplugHandler {
+    app: AppHandle,
+    running: Arc<AtomicBool>,
+}
+
+impl<T: UsbContext> rusb::Hotplug<T> for HotplugHandler {
+    fn device_arrived(&mut self, device: Device<T>) {
+        if !self.running.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(info) = correlate_serial(&device) {
+            let _ = self
+                .app
+                .emit("device-arrived", DeviceArrivedPayload { device: info });
+        }
+    }
+
+    fn device_left(&mut self, device: Device<T>) {
+        if !self.running.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(serial) = read_serial_number(&device) {
+            teardown_logcat(&self.app, &serial);
+            let _ = self
+                .app
+                .emit("device-departed", DeviceDepartedPayload { serial });
+        }
+    }
+}
+
+/// Start the USB monitor. Registers a libusb hotplug callback when the host supports it;
+/// otherwise falls back to diffing `list_devices` snapshots on a timer. A no-op if already
+/// running.
+pub fn start(app: AppHandle, state: &DeviceMonitorState) {
+    if state.running.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let running = state.running.clone();
+
+    thread::spawn(move || {
+        if rusb::has_hotplug() {
+            if let Err(e) = run_hotplug(app.clone(), running.clone()) {
+                eprintln!(
+                    "USB hotplug registration failed, falling back to polling: {}",
+                    e
+                );
+                run_poll_fallback(app, running);
+            }
+        } else {
+            run_poll_fallback(app, running);
+        }
+    });
+}
+
+/// Stop the USB monitor. The hotplug/poll loop notices on its next wakeup and exits.
+pub fn stop(state: &DeviceMonitorState) {
+    state.running.store(false, Ordering::SeqCst);
+}
+
+fn run_hotplug(app: AppHandle, running: Arc<AtomicBool>) -> Result<(), rusb::Error> {
+    let context = Context::new()?;
+    let _registration = HotplugBuilder::new().enumerate(false).register(
+        &context,
+        Box::new(HotplugHandler {
+            app,
+            running: running.clone(),
+        }),
+    )?;
+
+    while running.load(Ordering::Relaxed) {
+        context.handle_events(Some(Duration::from_millis(500)))?;
+    }
+
+    Ok(())
+}
+
+fn run_poll_fallback(app: AppHandle, running: Arc<AtomicBool>) {
+    let executor = AdbExecutor::new();
+    let mut last = executor.list_devices().unwrap_or_default();
+
+    while running.load(Ordering::Relaxed) {
+        thread::sleep(POLL_INTERVAL);
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let current = match executor.list_devices() {
+            Ok(devices) => devices,
+            Err(_) => continue,
+        };
+
+        for device in current.iter() {
+            if !last.iter().any(|d| d.id == device.id) {
+                let _ = app.emit(
+                    "device-arrived",
+                    DeviceArrivedPayload {
+                        device: device.clone(),
+                    },
+                );
+            }
+        }
+        for device in last.iter() {
+            if !current.iter().any(|d| d.id == device.id) {
+                teardown_logcat(&app, &device.id);
+                let _ = app.emit(
+                    "device-departed",
+                    DeviceDepartedPayload {
+                        serial: device.id.clone(),
+                    },
+                );
+            }
+        }
+
+        last = current;
+    }
+}
+
+/// Best-effort correlation of a USB device to an adb serial: read the device's serial-number
+/// string descriptor (the same value adb surfaces as the device serial for most Android
+/// devices) and cross-check it against a `list_devices` snapshot, retrying briefly since the
+/// adb server may not have finished enumerating the device yet.
+fn correlate_serial<T: UsbContext>(device: &Device<T>) -> Option<DeviceInfo> {
+    let serial = read_serial_number(device)?;
+    let executor = AdbExecutor::new();
+
+    for attempt in 0..CORRELATE_RETRIES {
+        if let Some(info) = executor
+            .list_devices()
+            .ok()?
+            .into_iter()
+            .find(|d| d.id == serial)
+        {
+            return Some(info);
+        }
+        if attempt + 1 < CORRELATE_RETRIES {
+            thread::sleep(CORRELATE_RETRY_DELAY);
+        }
+    }
+
+    None
+}
+
+fn read_serial_number<T: UsbContext>(device: &Device<T>) -> Option<String> {
+    let descriptor = device.device_descriptor().ok()?;
+    let handle = device.open().ok()?;
+    let timeout = Duration::from_millis(200);
+    let language = handle.read_languages(timeout).ok()?.into_iter().next()?;
+    handle
+        .read_serial_number_string(language, &descriptor, timeout)
+        .ok()
+}
+
+/// Kill any active logcat stream for a departed device, since there's no longer anything on
+/// the other end of the pipe to read from.
+fn teardown_logcat(app: &AppHandle, serial: &str) {
+    if let Some(state) = app.try_state::<LogcatState>() {
+        let mut streams = state.streams.lock().unwrap();
+        if let Some(mut child) = streams.remove(serial) {
+            let _ = child.kill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_device_monitor_state_starts_stopped() {
+        use super::DeviceMonitorState;
+        use std::sync::atomic::Ordering;
+
+        let state = DeviceMonitorState::new();
+        assert!(!state.running.load(Ordering::Relaxed));
+    }
+}
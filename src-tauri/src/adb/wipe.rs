@@ -0,0 +1,192 @@
+// Wipe - Drives a factory reset / targeted data-cache wipe through recovery instead of only
+// rebooting and leaving the user to navigate its menus by hand.
+//
+// Two channels exist for handing recovery a job. Newer builds expose a direct shell entry
+// point, `recovery --wipe_data`, that runs the wipe immediately without a reboot detour; we try
+// that first. Where it's unavailable we fall back to staging the command the platform's own
+// updater uses: recovery reads a one-directive-per-line script out of
+// `/cache/recovery/command` (the misc/bootloader-message channel) and auto-executes it the next
+// time it boots, so we write the script and reboot into recovery ourselves.
+
+use crate::adb::command_builder::quote_shell_arg;
+use crate::adb::executor::AdbExecutor;
+use crate::command_utils::hidden_command;
+use crate::error::AppError;
+
+/// Which wipe operations to stage.
+#[derive(Debug, Clone, Copy)]
+pub struct WipeOptions {
+    pub wipe_data: bool,
+    pub wipe_cache: bool,
+}
+
+impl WipeOptions {
+    fn recovery_directives(&self) -> Vec<&'static str> {
+        let mut directives = Vec::new();
+        if self.wipe_data {
+            directives.push("--wipe_data");
+        }
+        if self.wipe_cache {
+            directives.push("--wipe_cache");
+        }
+        directives
+    }
+}
+
+/// How the wipe was actually carried out.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WipeMethod {
+    /// `recovery --wipe_data` ran directly over the shell, no reboot needed.
+    Direct,
+    /// The command was staged into `/cache/recovery/command` and the device rebooted into
+    /// recovery to execute it.
+    StagedReboot,
+    /// Neither path worked, most likely because the device is locked/verified-boot and refused
+    /// to touch `/cache`.
+    Rejected,
+}
+
+/// Outcome of a [`factory_reset`] attempt.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WipeOutcome {
+    pub method: WipeMethod,
+    pub detail: String,
+}
+
+/// Build the `/cache/recovery/command` script body recovery reads on boot: one directive per
+/// line, terminated with a trailing newline.
+fn build_command_script(options: &WipeOptions) -> String {
+    let mut script = String::new();
+    for directive in options.recovery_directives() {
+        script.push_str(directive);
+        script.push('\n');
+    }
+    script
+}
+
+/// Try `recovery --wipe_data` directly over the shell. Returns `Ok(true)` if recovery accepted
+/// and ran it, `Ok(false)` if the device rejected it (e.g. locked bootloader denying access to
+/// recovery), or `Err` if the shell invocation itself failed.
+fn try_direct_wipe(device_id: &str, options: &WipeOptions) -> Result<bool, AppError> {
+    if !options.wipe_data {
+        // `recovery` only exposes a direct entry point for a full data wipe; targeted
+        // cache-only wipes always go through the staged command.
+        return Ok(false);
+    }
+
+    let executor = AdbExecutor::new();
+    let adb_path = executor.get_adb_path();
+
+    let output = hidden_command(adb_path)
+        .args(["-s", device_id, "shell", "recovery", "--wipe_data"])
+        .output()
+        .map_err(|e| {
+            AppError::new(
+                "FACTORY_RESET_FAILED",
+                &format!("Failed to invoke recovery: {}", e),
+            )
+        })?;
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .to_lowercase();
+
+    let denied = combined.contains("not allowed")
+        || combined.contains("permission denied")
+        || combined.contains("not found")
+        || combined.contains("no such file");
+
+    Ok(output.status.success() && !denied)
+}
+
+/// Write `options` into `/cache/recovery/command` so recovery picks it up the next time it
+/// boots, then reboot into recovery to trigger it.
+fn stage_and_reboot(device_id: &str, options: &WipeOptions) -> Result<(), AppError> {
+    let executor = AdbExecutor::new();
+    let adb_path = executor.get_adb_path();
+    let script = build_command_script(options);
+
+    let output = hidden_command(adb_path)
+        .args([
+            "-s",
+            device_id,
+            "shell",
+            "mkdir",
+            "-p",
+            "/cache/recovery",
+            "&&",
+            "printf",
+            "%s",
+            &quote_shell_arg(&script),
+            ">",
+            "/cache/recovery/command",
+        ])
+        .output()
+        .map_err(|e| {
+            AppError::new(
+                "FACTORY_RESET_FAILED",
+                &format!("Failed to stage recovery command: {}", e),
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::with_details(
+            "FACTORY_RESET_FAILED",
+            "Device rejected the staged recovery command; it may be locked or verified-boot may prevent writes to /cache",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+
+    crate::commands::device_actions::reboot_device(device_id.to_string(), Some("recovery".to_string()))
+}
+
+/// Trigger a factory reset / targeted wipe via recovery: try the direct `recovery --wipe_data`
+/// entry point first, and fall back to staging a recovery command plus a reboot when it's
+/// unavailable or rejected.
+pub fn factory_reset(device_id: &str, options: &WipeOptions) -> Result<WipeOutcome, AppError> {
+    if try_direct_wipe(device_id, options)? {
+        return Ok(WipeOutcome {
+            method: WipeMethod::Direct,
+            detail: "recovery --wipe_data executed directly".to_string(),
+        });
+    }
+
+    match stage_and_reboot(device_id, options) {
+        Ok(()) => Ok(WipeOutcome {
+            method: WipeMethod::StagedReboot,
+            detail: "Recovery command staged; device is rebooting into recovery to execute it"
+                .to_string(),
+        }),
+        Err(e) => Ok(WipeOutcome {
+            method: WipeMethod::Rejected,
+            detail: e.message,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_command_script_data_and_cache() {
+        let options = WipeOptions {
+            wipe_data: true,
+            wipe_cache: true,
+        };
+        assert_eq!(build_command_script(&options), "--wipe_data\n--wipe_cache\n");
+    }
+
+    #[test]
+    fn test_build_command_script_cache_only() {
+        let options = WipeOptions {
+            wipe_data: false,
+            wipe_cache: true,
+        };
+        assert_eq!(build_command_script(&options), "--wipe_cache\n");
+    }
+}
@@ -0,0 +1,612 @@
+// APK Manifest Parsing - Reads the real package identity out of an APK instead of trusting its
+// file extension.
+//
+// An APK is a ZIP. This walks the central directory to find `AndroidManifest.xml` (stored in
+// Android's binary XML format, AXML, not plain text), enumerates `lib/<abi>/` entries for
+// supported ABIs, and checks for a signing block so `ApkInfo` can catch a corrupt, unsigned, or
+// incompatible APK before a user ever runs an install.
+
+use std::path::Path;
+
+/// Metadata recovered from an APK's ZIP and AXML structure.
+#[derive(Debug, Clone, Default)]
+pub struct ApkMetadata {
+    pub package: Option<String>,
+    pub version_code: Option<u32>,
+    pub min_sdk_version: Option<u32>,
+    pub abis: Vec<String>,
+    pub signed: bool,
+}
+
+/// Parse `path` as an APK. Returns `None` if it isn't a readable ZIP at all; a ZIP that parses
+/// but has no `AndroidManifest.xml` (or one AXML can't make sense of) still comes back with
+/// `package: None`, which `ApkInfo::from_path` treats as invalid.
+pub fn parse(path: &Path) -> Option<ApkMetadata> {
+    let data = std::fs::read(path).ok()?;
+    let entries = zip::read_entries(&data)?;
+
+    let mut metadata = ApkMetadata::default();
+
+    for entry in &entries {
+        if entry.name == "AndroidManifest.xml" {
+            if let Some(xml) = zip::read_entry_data(&data, entry) {
+                let fields = axml::manifest_fields(&xml);
+                metadata.package = fields.package;
+                metadata.version_code = fields.version_code;
+                metadata.min_sdk_version = fields.min_sdk_version;
+            }
+        } else if let Some(abi) = entry
+            .name
+            .strip_prefix("lib/")
+            .and_then(|rest| rest.split('/').next())
+        {
+            if !abi.is_empty() && !metadata.abis.iter().any(|a| a == abi) {
+                metadata.abis.push(abi.to_string());
+            }
+        }
+    }
+
+    let has_signature_file = entries.iter().any(|e| {
+        e.name.starts_with("META-INF/")
+            && (e.name.ends_with(".RSA") || e.name.ends_with(".DSA") || e.name.ends_with(".EC"))
+    });
+    metadata.signed = has_signature_file || has_v2_signing_block(&data);
+
+    Some(metadata)
+}
+
+/// Look for the APK Signing Block's magic footer (`APK Sig Block 42`), which v2+ signatures are
+/// wrapped in just before the central directory. A plain byte search is good enough here; we
+/// only need to know the block is present, not parse its contents.
+fn has_v2_signing_block(data: &[u8]) -> bool {
+    const MAGIC: &[u8] = b"APK Sig Block 42";
+    data.windows(MAGIC.len()).any(|w| w == MAGIC)
+}
+
+/// Minimal ZIP central-directory reader: just enough to list entries and pull out one at a time
+/// by name, without pulling in a full archive crate for a handful of lookups. `pub(crate)` so
+/// `adb::bootstrap` can reuse it to extract the platform-tools archive.
+pub(crate) mod zip {
+    use std::io::Read;
+
+    const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+    const CENTRAL_DIR_SIGNATURE: u32 = 0x0201_4b50;
+    const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+
+    pub struct ZipEntry {
+        pub name: String,
+        pub compression: u16,
+        pub compressed_size: u32,
+        pub local_header_offset: u32,
+        pub crc32: u32,
+    }
+
+    /// Walk the central directory and return every entry's name, compression method, and where
+    /// its local header lives.
+    pub fn read_entries(data: &[u8]) -> Option<Vec<ZipEntry>> {
+        let eocd_offset = find_eocd(data)?;
+        let cd_offset = read_u32(data, eocd_offset + 16)? as usize;
+        let cd_record_count = read_u16(data, eocd_offset + 10)? as usize;
+
+        let mut entries = Vec::with_capacity(cd_record_count);
+        let mut pos = cd_offset;
+
+        for _ in 0..cd_record_count {
+            if read_u32(data, pos)? != CENTRAL_DIR_SIGNATURE {
+                break;
+            }
+
+            let compression = read_u16(data, pos + 10)?;
+            let crc32 = read_u32(data, pos + 16)?;
+            let compressed_size = read_u32(data, pos + 20)?;
+            let name_len = read_u16(data, pos + 28)? as usize;
+            let extra_len = read_u16(data, pos + 30)? as usize;
+            let comment_len = read_u16(data, pos + 32)? as usize;
+            let local_header_offset = read_u32(data, pos + 42)?;
+
+            let name_start = pos + 46;
+            let name = String::from_utf8_lossy(data.get(name_start..name_start + name_len)?)
+                .to_string();
+
+            entries.push(ZipEntry {
+                name,
+                compression,
+                compressed_size,
+                local_header_offset,
+                crc32,
+            });
+
+            pos = name_start + name_len + extra_len + comment_len;
+        }
+
+        Some(entries)
+    }
+
+    /// Read (and decompress, if needed) the file data for a single entry via its local header.
+    pub fn read_entry_data(data: &[u8], entry: &ZipEntry) -> Option<Vec<u8>> {
+        let pos = entry.local_header_offset as usize;
+        if read_u32(data, pos)? != LOCAL_HEADER_SIGNATURE {
+            return None;
+        }
+
+        let name_len = read_u16(data, pos + 26)? as usize;
+        let extra_len = read_u16(data, pos + 28)? as usize;
+        let data_start = pos + 30 + name_len + extra_len;
+        let compressed = data.get(data_start..data_start + entry.compressed_size as usize)?;
+
+        match entry.compression {
+            0 => Some(compressed.to_vec()),
+            8 => {
+                let mut decoder = flate2::read::DeflateDecoder::new(compressed);
+                let mut out = Vec::new();
+                decoder.read_to_end(&mut out).ok()?;
+                Some(out)
+            }
+            _ => None,
+        }
+    }
+
+    /// Standard IEEE 802.3 CRC-32 (the same variant the ZIP format stores per entry), computed
+    /// byte-at-a-time so `adb::bootstrap` can confirm an extracted entry matches the archive's
+    /// own checksum without pulling in a crc crate for one lookup.
+    pub fn crc32(data: &[u8]) -> u32 {
+        const POLY: u32 = 0xEDB8_8320;
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    /// Scan backwards from the end of the file for the End Of Central Directory signature,
+    /// which can be preceded by an arbitrary-length (and arbitrary-content) comment.
+    fn find_eocd(data: &[u8]) -> Option<usize> {
+        if data.len() < 22 {
+            return None;
+        }
+        let search_start = data.len().saturating_sub(22 + u16::MAX as usize);
+        (search_start..=data.len() - 22)
+            .rev()
+            .find(|&i| read_u32(data, i) == Some(EOCD_SIGNATURE))
+    }
+
+    fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+        data.get(pos..pos + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Build a single-entry, stored-method ZIP for tests, without pulling in a real zip
+        /// writer.
+        fn build_test_zip(name: &str, content: &[u8]) -> Vec<u8> {
+            let mut out = Vec::new();
+            let local_header_offset = 0u32;
+
+            out.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            out.extend_from_slice(&0u16.to_le_bytes()); // flags
+            out.extend_from_slice(&0u16.to_le_bytes()); // compression: stored
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod date
+            out.extend_from_slice(&0u32.to_le_bytes()); // crc32
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes()); // compressed size
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes()); // uncompressed size
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(content);
+
+            let cd_offset = out.len() as u32;
+            out.extend_from_slice(&CENTRAL_DIR_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // version made by
+            out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            out.extend_from_slice(&0u16.to_le_bytes()); // flags
+            out.extend_from_slice(&0u16.to_le_bytes()); // compression: stored
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod date
+            out.extend_from_slice(&0u32.to_le_bytes()); // crc32
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+            out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            out.extend_from_slice(&0u16.to_le_bytes()); // disk number start
+            out.extend_from_slice(&0u16.to_le_bytes()); // internal attrs
+            out.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+            out.extend_from_slice(&local_header_offset.to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+            let cd_size = out.len() as u32 - cd_offset;
+
+            out.extend_from_slice(&EOCD_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // disk number
+            out.extend_from_slice(&0u16.to_le_bytes()); // cd start disk
+            out.extend_from_slice(&1u16.to_le_bytes()); // records on this disk
+            out.extend_from_slice(&1u16.to_le_bytes()); // total records
+            out.extend_from_slice(&cd_size.to_le_bytes());
+            out.extend_from_slice(&cd_offset.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+            out
+        }
+
+        #[test]
+        fn test_read_entries_finds_stored_file() {
+            let zip = build_test_zip("AndroidManifest.xml", b"hello");
+            let entries = read_entries(&zip).unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].name, "AndroidManifest.xml");
+            assert_eq!(entries[0].compression, 0);
+        }
+
+        #[test]
+        fn test_read_entry_data_roundtrips_stored_content() {
+            let zip = build_test_zip("lib/arm64-v8a/libfoo.so", b"native bytes");
+            let entries = read_entries(&zip).unwrap();
+            let data = read_entry_data(&zip, &entries[0]).unwrap();
+            assert_eq!(data, b"native bytes");
+        }
+
+        #[test]
+        fn test_crc32_matches_known_value() {
+            // "123456789" is the standard CRC-32/ISO-HDLC check string; a correct
+            // implementation always produces this value for it.
+            assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        }
+    }
+}
+
+/// Android Binary XML (AXML) parsing, just deep enough to pull `package`/`versionCode` off
+/// `<manifest>` and `minSdkVersion` off `<uses-sdk>`.
+mod axml {
+    const CHUNK_STRING_POOL: u16 = 0x0001;
+    const CHUNK_START_ELEMENT: u16 = 0x0102;
+
+    const TYPE_STRING: u8 = 0x03;
+
+    const UTF8_FLAG: u32 = 0x0000_0100;
+
+    #[derive(Debug, Default)]
+    pub struct ManifestFields {
+        pub package: Option<String>,
+        pub version_code: Option<u32>,
+        pub min_sdk_version: Option<u32>,
+    }
+
+    /// Walk the top-level XML chunk's children, tracking the shared string pool, and pull the
+    /// fields we care about off the `<manifest>` and `<uses-sdk>` start-element chunks.
+    pub fn manifest_fields(data: &[u8]) -> ManifestFields {
+        let mut fields = ManifestFields::default();
+        let mut strings: Vec<String> = Vec::new();
+
+        // Skip the outer RES_XML_TYPE (0x0003) header.
+        let header_size = match read_u16(data, 2) {
+            Some(h) => h,
+            None => return fields,
+        };
+        let mut pos = header_size as usize;
+
+        while pos + 8 <= data.len() {
+            let chunk_type = match read_u16(data, pos) {
+                Some(t) => t,
+                None => break,
+            };
+            let chunk_size = match read_u32(data, pos + 4) {
+                Some(s) => s,
+                None => break,
+            };
+            if chunk_size == 0 || pos + chunk_size as usize > data.len() {
+                break;
+            }
+
+            match chunk_type {
+                CHUNK_STRING_POOL => {
+                    strings = parse_string_pool(&data[pos..pos + chunk_size as usize]);
+                }
+                CHUNK_START_ELEMENT => {
+                    parse_start_element(&data[pos..pos + chunk_size as usize], &strings, &mut fields);
+                }
+                _ => {}
+            }
+
+            pos += chunk_size as usize;
+        }
+
+        fields
+    }
+
+    /// `ResXMLTree_node` (header) + `ResXMLTree_attrExt`: pull the tag name and walk its
+    /// attributes, recording the ones `manifest`/`uses-sdk` care about.
+    fn parse_start_element(chunk: &[u8], strings: &[String], fields: &mut ManifestFields) {
+        // node header: ResChunk_header(8) + lineNumber(4) + comment(4) = 16 bytes, then
+        // ResXMLTree_attrExt begins.
+        let ext_start = 16;
+        let name_idx = match read_u32(chunk, ext_start + 4) {
+            Some(n) => n,
+            None => return,
+        };
+        let tag_name = match strings.get(name_idx as usize) {
+            Some(n) => n,
+            None => return,
+        };
+
+        if tag_name != "manifest" && tag_name != "uses-sdk" {
+            return;
+        }
+
+        let attribute_start = match read_u16(chunk, ext_start + 8) {
+            Some(a) => a,
+            None => return,
+        };
+        let attribute_size = match read_u16(chunk, ext_start + 10) {
+            Some(a) => a,
+            None => return,
+        };
+        let attribute_count = match read_u16(chunk, ext_start + 12) {
+            Some(a) => a,
+            None => return,
+        };
+
+        let attrs_base = ext_start + attribute_start as usize;
+        for i in 0..attribute_count as usize {
+            let attr_pos = attrs_base + i * attribute_size as usize;
+            let attr_name_idx = match read_u32(chunk, attr_pos + 4) {
+                Some(a) => a,
+                None => continue,
+            };
+            let attr_name = match strings.get(attr_name_idx as usize) {
+                Some(a) => a,
+                None => continue,
+            };
+
+            // Res_value sits right after ns(4)/name(4)/rawValue(4): size(2), res0(1), dataType(1), data(4)
+            let value_pos = attr_pos + 12;
+            let data_type = match chunk.get(value_pos + 3).copied() {
+                Some(d) => d,
+                None => continue,
+            };
+            let raw_data = match read_u32(chunk, value_pos + 4) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            match attr_name.as_str() {
+                "package" => {
+                    fields.package = resolve_value(data_type, raw_data, strings);
+                }
+                "versionCode" => {
+                    fields.version_code = resolve_int(data_type, raw_data);
+                }
+                "minSdkVersion" => {
+                    fields.min_sdk_version = resolve_int(data_type, raw_data);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn resolve_value(data_type: u8, raw_data: u32, strings: &[String]) -> Option<String> {
+        if data_type == TYPE_STRING {
+            strings.get(raw_data as usize).cloned()
+        } else {
+            None
+        }
+    }
+
+    fn resolve_int(data_type: u8, raw_data: u32) -> Option<u32> {
+        if data_type == TYPE_STRING {
+            None
+        } else {
+            Some(raw_data)
+        }
+    }
+
+    /// `ResStringPool_header` + offsets table + string data. Handles both the UTF-16 and
+    /// UTF8_FLAG encodings.
+    fn parse_string_pool(chunk: &[u8]) -> Vec<String> {
+        let string_count = match read_u32(chunk, 8) {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        let flags = match read_u32(chunk, 16) {
+            Some(f) => f,
+            None => return Vec::new(),
+        };
+        let strings_start = match read_u32(chunk, 20) {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+
+        let is_utf8 = flags & UTF8_FLAG != 0;
+        let offsets_start = 28;
+        let mut strings = Vec::with_capacity(string_count as usize);
+
+        for i in 0..string_count as usize {
+            let offset = match read_u32(chunk, offsets_start + i * 4) {
+                Some(o) => o,
+                None => break,
+            };
+            let string_pos = strings_start as usize + offset as usize;
+            let decoded = if is_utf8 {
+                decode_utf8_string(chunk, string_pos)
+            } else {
+                decode_utf16_string(chunk, string_pos)
+            };
+            strings.push(decoded.unwrap_or_default());
+        }
+
+        strings
+    }
+
+    /// UTF8_FLAG strings: a UTF-16 length (unused here beyond skipping it), a UTF-8 byte length,
+    /// then that many UTF-8 bytes.
+    fn decode_utf8_string(chunk: &[u8], pos: usize) -> Option<String> {
+        let mut cursor = pos;
+        let (_utf16_len, consumed) = decode_length_utf8(chunk, cursor)?;
+        cursor += consumed;
+        let (utf8_len, consumed) = decode_length_utf8(chunk, cursor)?;
+        cursor += consumed;
+
+        let bytes = chunk.get(cursor..cursor + utf8_len)?;
+        Some(String::from_utf8_lossy(bytes).to_string())
+    }
+
+    /// UTF-16 strings: a length prefix (1 or 2 `u16`s), then that many UTF-16 code units.
+    fn decode_utf16_string(chunk: &[u8], pos: usize) -> Option<String> {
+        let (len, consumed) = decode_length_utf16(chunk, pos)?;
+        let start = pos + consumed;
+        let units: Vec<u16> = (0..len)
+            .map(|i| read_u16(chunk, start + i * 2))
+            .collect::<Option<_>>()?;
+        Some(char::decode_utf16(units).map(|r| r.unwrap_or('\u{FFFD}')).collect())
+    }
+
+    /// Android's variable-length encoding: a single byte normally, or two bytes (high bit of
+    /// the first set) for lengths over 0x7f.
+    fn decode_length_utf8(chunk: &[u8], pos: usize) -> Option<(usize, usize)> {
+        let first = *chunk.get(pos)? as usize;
+        if first & 0x80 != 0 {
+            let second = *chunk.get(pos + 1)? as usize;
+            Some((((first & 0x7f) << 8) | second, 2))
+        } else {
+            Some((first, 1))
+        }
+    }
+
+    /// Same variable-length scheme as [`decode_length_utf8`], but over `u16` units for UTF-16
+    /// strings.
+    fn decode_length_utf16(chunk: &[u8], pos: usize) -> Option<(usize, usize)> {
+        let first = read_u16(chunk, pos)? as usize;
+        if first & 0x8000 != 0 {
+            let second = read_u16(chunk, pos + 2)? as usize;
+            Some((((first & 0x7fff) << 16) | second, 4))
+        } else {
+            Some((first, 2))
+        }
+    }
+
+    fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+        data.get(pos..pos + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Hand-build a minimal AXML document with a UTF-8 string pool containing `manifest`,
+        /// `package`, and a package name, plus a single `<manifest package="...">` start
+        /// element, to exercise the walker end to end without a real `aapt`-built manifest.
+        fn build_test_axml(package_name: &str) -> Vec<u8> {
+            let strings = ["manifest", "package", package_name];
+
+            // --- string pool chunk ---
+            let mut string_data = Vec::new();
+            let mut offsets = Vec::new();
+            for s in &strings {
+                offsets.push(string_data.len() as u32);
+                string_data.push(s.len() as u8); // utf16 length (unused, fits in one byte)
+                string_data.push(s.len() as u8); // utf8 length
+                string_data.extend_from_slice(s.as_bytes());
+                string_data.push(0); // NUL terminator
+            }
+
+            let pool_header_size = 28u32;
+            let offsets_size = (strings.len() * 4) as u32;
+            let strings_start = pool_header_size + offsets_size;
+            let pool_chunk_size = strings_start + string_data.len() as u32;
+
+            let mut pool_chunk = Vec::new();
+            pool_chunk.extend_from_slice(&CHUNK_STRING_POOL.to_le_bytes());
+            pool_chunk.extend_from_slice(&(pool_header_size as u16).to_le_bytes());
+            pool_chunk.extend_from_slice(&pool_chunk_size.to_le_bytes());
+            pool_chunk.extend_from_slice(&(strings.len() as u32).to_le_bytes()); // stringCount
+            pool_chunk.extend_from_slice(&0u32.to_le_bytes()); // styleCount
+            pool_chunk.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+            pool_chunk.extend_from_slice(&strings_start.to_le_bytes());
+            pool_chunk.extend_from_slice(&0u32.to_le_bytes()); // stylesStart
+            for o in &offsets {
+                pool_chunk.extend_from_slice(&o.to_le_bytes());
+            }
+            pool_chunk.extend_from_slice(&string_data);
+
+            // --- start-element chunk: <manifest package="<package_name>"> ---
+            let attr_ext_start = 16u32;
+            let attribute_start = 20u16; // bytes from ResXMLTree_attrExt start to attributes
+            let attribute_size = 20u16;
+            let attribute_count = 1u16;
+
+            let mut element_chunk = Vec::new();
+            let element_header_size = 16u16;
+            let element_size = attr_ext_start + attribute_start as u32 + attribute_size as u32;
+            element_chunk.extend_from_slice(&CHUNK_START_ELEMENT.to_le_bytes());
+            element_chunk.extend_from_slice(&element_header_size.to_le_bytes());
+            element_chunk.extend_from_slice(&element_size.to_le_bytes());
+            element_chunk.extend_from_slice(&0u32.to_le_bytes()); // lineNumber
+            element_chunk.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // comment
+            element_chunk.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // ns
+            element_chunk.extend_from_slice(&0u32.to_le_bytes()); // name idx -> "manifest"
+            element_chunk.extend_from_slice(&attribute_start.to_le_bytes());
+            element_chunk.extend_from_slice(&attribute_size.to_le_bytes());
+            element_chunk.extend_from_slice(&attribute_count.to_le_bytes());
+            element_chunk.extend_from_slice(&0u16.to_le_bytes()); // idIndex
+            element_chunk.extend_from_slice(&0u16.to_le_bytes()); // classIndex
+            element_chunk.extend_from_slice(&0u16.to_le_bytes()); // styleIndex
+            // attribute: ns(4), name(4) -> "package", rawValue(4), Res_value(size2,res0(1),type(1),data4)
+            element_chunk.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+            element_chunk.extend_from_slice(&1u32.to_le_bytes()); // "package"
+            element_chunk.extend_from_slice(&2u32.to_le_bytes()); // rawValue -> package_name string
+            element_chunk.extend_from_slice(&8u16.to_le_bytes()); // Res_value.size
+            element_chunk.push(0); // res0
+            element_chunk.push(TYPE_STRING);
+            element_chunk.extend_from_slice(&2u32.to_le_bytes()); // data -> string idx of package_name
+
+            // --- top-level RES_XML_TYPE wrapper ---
+            let mut doc = Vec::new();
+            doc.extend_from_slice(&0x0003u16.to_le_bytes());
+            doc.extend_from_slice(&8u16.to_le_bytes()); // headerSize
+            let total_size = 8 + pool_chunk.len() + element_chunk.len();
+            doc.extend_from_slice(&(total_size as u32).to_le_bytes());
+            doc.extend_from_slice(&pool_chunk);
+            doc.extend_from_slice(&element_chunk);
+
+            doc
+        }
+
+        #[test]
+        fn test_manifest_fields_extracts_package() {
+            let doc = build_test_axml("com.example.app");
+            let fields = manifest_fields(&doc);
+            assert_eq!(fields.package.as_deref(), Some("com.example.app"));
+        }
+
+        #[test]
+        fn test_decode_length_utf8_single_byte() {
+            assert_eq!(decode_length_utf8(&[0x05], 0), Some((5, 1)));
+        }
+
+        #[test]
+        fn test_decode_length_utf8_two_byte() {
+            assert_eq!(decode_length_utf8(&[0x81, 0x02], 0), Some((0x102, 2)));
+        }
+    }
+}
@@ -0,0 +1,903 @@
+// APK Module - APK file handling and installation
+// Manages APK validation and installation process
+
+pub mod manifest;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Information about an APK file
+#[derive(Debug, Clone, Serialize)]
+pub struct ApkInfo {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub valid: bool,
+    pub last_modified: Option<u128>,
+    /// The manifest's `package` attribute, e.g. `com.example.app`.
+    pub package: Option<String>,
+    /// The manifest's `android:versionCode`.
+    pub version_code: Option<u32>,
+    /// `<uses-sdk android:minSdkVersion>`.
+    pub min_sdk_version: Option<u32>,
+    /// Native library ABIs bundled under `lib/<abi>/`, e.g. `arm64-v8a`.
+    pub abis: Vec<String>,
+    /// Whether a `META-INF/*.RSA|.DSA|.EC` signature file or a v2 signing block was found.
+    pub signed: bool,
+}
+
+impl ApkInfo {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path_obj = Path::new(path);
+
+        if !path_obj.exists() {
+            return None;
+        }
+
+        let file_name = path_obj
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown.apk")
+            .to_string();
+
+        let metadata = std::fs::metadata(path).ok()?;
+        let size_bytes = metadata.len();
+
+        let last_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis());
+
+        let has_apk_extension = path_obj
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase() == "apk")
+            .unwrap_or(false);
+
+        // Parse the ZIP/AXML structure so a corrupt, unsigned, or incompatible APK is caught
+        // here instead of surfacing only after a failed `adb install`.
+        let parsed = manifest::parse(path_obj);
+        let valid = has_apk_extension
+            && parsed
+                .as_ref()
+                .map(|m| m.package.is_some())
+                .unwrap_or(false);
+
+        let (package, version_code, min_sdk_version, abis, signed) = match parsed {
+            Some(m) => (m.package, m.version_code, m.min_sdk_version, m.abis, m.signed),
+            None => (None, None, None, Vec::new(), false),
+        };
+
+        Some(Self {
+            path: path.to_string(),
+            file_name,
+            size_bytes,
+            valid,
+            last_modified,
+            package,
+            version_code,
+            min_sdk_version,
+            abis,
+            signed,
+        })
+    }
+}
+
+/// Target storage volume to stage the APK on before installation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AndroidStorageInput {
+    #[default]
+    Auto,
+    App,
+    Internal,
+    Sdcard,
+}
+
+/// Which path `ApkInstaller::install` actually took to get the APK onto the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallStrategy {
+    /// Plain `adb install`.
+    Direct,
+    /// Pushed to a temp remote path and ran `pm install`, either after a plain `adb install`
+    /// failed or directly via `install_streamed_push`.
+    PushThenPmInstall,
+    /// Streamed straight into `package install` over `abb_exec`, skipping the temp copy.
+    Streamed,
+    /// Taken on an `abb_exec` device that holds a persisted base copy from an earlier install:
+    /// the blocks whose hash changed are pushed over sync and patched into a copy of that base,
+    /// which is then installed with `pm install`. Only those changed blocks crossed the wire.
+    Incremental,
+    /// `adb install-multiple -r` for a split APK set (app bundle base + configs).
+    MultipleDirect,
+    /// A split APK set streamed over `abb_exec` using an `install-create`/`install-write`/
+    /// `install-commit` session instead of `adb install-multiple`.
+    MultipleStreamed,
+}
+
+/// Result of an APK installation
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallResult {
+    pub success: bool,
+    pub device_id: String,
+    pub message: String,
+    pub error_code: Option<String>,
+    pub strategy: Option<InstallStrategy>,
+    pub storage: Option<AndroidStorageInput>,
+}
+
+impl InstallResult {
+    pub fn success(device_id: &str, message: &str) -> Self {
+        Self {
+            success: true,
+            device_id: device_id.to_string(),
+            message: message.to_string(),
+            error_code: None,
+            strategy: None,
+            storage: None,
+        }
+    }
+
+    pub fn failure(device_id: &str, message: &str, error_code: Option<&str>) -> Self {
+        Self {
+            success: false,
+            device_id: device_id.to_string(),
+            message: message.to_string(),
+            error_code: error_code.map(|s| s.to_string()),
+            strategy: None,
+            storage: None,
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: InstallStrategy, storage: AndroidStorageInput) -> Self {
+        self.strategy = Some(strategy);
+        self.storage = Some(storage);
+        self
+    }
+}
+
+/// Map ADB install error codes to user-friendly messages
+pub fn map_install_error(error_output: &str) -> (String, Option<String>) {
+    let error_mappings = [
+        (
+            "INSTALL_FAILED_ALREADY_EXISTS",
+            "App is already installed. Try uninstalling first.",
+        ),
+        (
+            "INSTALL_FAILED_INSUFFICIENT_STORAGE",
+            "Not enough storage space on device.",
+        ),
+        (
+            "INSTALL_FAILED_INVALID_APK",
+            "Invalid or corrupted APK file.",
+        ),
+        (
+            "INSTALL_FAILED_VERSION_DOWNGRADE",
+            "Cannot install older version over newer one.",
+        ),
+        (
+            "INSTALL_FAILED_USER_RESTRICTED",
+            "Installation blocked by device policy.",
+        ),
+        (
+            "INSTALL_FAILED_UPDATE_INCOMPATIBLE",
+            "Update incompatible with installed version.",
+        ),
+        (
+            "INSTALL_PARSE_FAILED_NO_CERTIFICATES",
+            "APK is not signed properly.",
+        ),
+        (
+            "INSTALL_FAILED_OLDER_SDK",
+            "App requires newer Android version.",
+        ),
+        (
+            "INSTALL_FAILED_CONFLICTING_PROVIDER",
+            "Conflicts with another installed app.",
+        ),
+        (
+            "INSTALL_FAILED_NO_MATCHING_ABIS",
+            "App not compatible with device architecture.",
+        ),
+    ];
+
+    for (code, message) in error_mappings {
+        if error_output.contains(code) {
+            return (message.to_string(), Some(code.to_string()));
+        }
+    }
+
+    // Default error message
+    (
+        "Installation failed. Check device connection and try again.".to_string(),
+        None,
+    )
+}
+
+/// Helper for APK installation
+pub struct ApkInstaller<'a> {
+    executor: &'a crate::adb::AdbExecutor,
+}
+
+impl<'a> ApkInstaller<'a> {
+    pub fn new(executor: &'a crate::adb::AdbExecutor) -> Self {
+        Self { executor }
+    }
+
+    /// Install APK on device, auto-selecting a storage target.
+    pub fn install(&self, device_id: &str, apk_path: &str) -> InstallResult {
+        self.install_with_storage(device_id, apk_path, AndroidStorageInput::Auto)
+    }
+
+    /// Install APK on device, targeting the given storage volume.
+    ///
+    /// If the device reports the `abb_exec` feature, the APK is streamed straight into
+    /// `package install` over the abb channel (skipping the temp-copy-then-`pm install` dance
+    /// entirely), using a local block-hash manifest to tell a fresh install apart from a
+    /// same-package update. Devices without `abb_exec` fall back to the classic path: a plain
+    /// `adb install` first, and if that fails, push-to-temp-then-`pm install`.
+    pub fn install_with_storage(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        storage: AndroidStorageInput,
+    ) -> InstallResult {
+        // Verify APK file exists
+        if !std::path::Path::new(apk_path).exists() {
+            return InstallResult::failure(device_id, "APK file not found", None);
+        }
+
+        if crate::adb::protocol::device_features(device_id)
+            .map(|features| features.iter().any(|f| f == "abb_exec"))
+            .unwrap_or(false)
+        {
+            match self.install_streamed_or_incremental(device_id, apk_path) {
+                Ok((message, strategy)) => {
+                    return InstallResult::success(device_id, &message)
+                        .with_strategy(strategy, storage);
+                }
+                Err(_) => {
+                    // Fall through to the classic path below (e.g. `abb_exec` advertised but
+                    // the daemon on this ROM doesn't actually implement the install verb).
+                }
+            }
+        }
+
+        let output = self.executor.run_with_retry(
+            || {
+                let mut cmd = crate::command_utils::hidden_command(self.executor.get_adb_path());
+                cmd.args(["-s", device_id, "install", "-r", apk_path]);
+                cmd
+            },
+            std::time::Duration::from_secs(120),
+            0,
+        );
+
+        match output {
+            Ok(result) => {
+                let stdout = String::from_utf8_lossy(&result.stdout);
+                let stderr = String::from_utf8_lossy(&result.stderr);
+                let combined = format!("{}{}", stdout, stderr);
+
+                if result.status.success() && combined.contains("Success") {
+                    return InstallResult::success(device_id, "APK installed successfully")
+                        .with_strategy(InstallStrategy::Direct, storage);
+                }
+
+                // `adb install` failed: fall back to push-then-pm-install.
+                match self.push_then_pm_install(device_id, apk_path) {
+                    Ok(message) => InstallResult::success(device_id, &message)
+                        .with_strategy(InstallStrategy::PushThenPmInstall, storage),
+                    Err(_) => {
+                        let (message, error_code) = map_install_error(&combined);
+
+                        if error_code.as_deref() == Some("INSTALL_PARSE_FAILED_NO_CERTIFICATES") {
+                            if let Some(result) =
+                                self.try_auto_sign_and_install(device_id, apk_path, storage)
+                            {
+                                return result;
+                            }
+                        }
+
+                        InstallResult::failure(device_id, &message, error_code.as_deref())
+                    }
+                }
+            }
+            Err(e) => InstallResult::failure(device_id, &format!("Failed to run adb: {}", e), None),
+        }
+    }
+
+    /// When `adb install` fails with `INSTALL_PARSE_FAILED_NO_CERTIFICATES`, sign the APK with
+    /// an ephemeral debug keystore and retry the install once before giving up.
+    fn try_auto_sign_and_install(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        storage: AndroidStorageInput,
+    ) -> Option<InstallResult> {
+        let signed = crate::signing::ApkSigner::new().sign(apk_path, None).ok()?;
+        if !signed.success {
+            return None;
+        }
+
+        let output = self
+            .executor
+            .run_with_retry(
+                || {
+                    let mut cmd =
+                        crate::command_utils::hidden_command(self.executor.get_adb_path());
+                    cmd.args(["-s", device_id, "install", "-r", &signed.apk_path]);
+                    cmd
+                },
+                std::time::Duration::from_secs(120),
+                0,
+            )
+            .ok()?;
+
+        let combined = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        if output.status.success() && combined.contains("Success") {
+            Some(
+                InstallResult::success(device_id, "APK auto-signed and installed successfully")
+                    .with_strategy(InstallStrategy::Direct, storage),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Push the APK to a temp path on `/data/local/tmp` over the native sync protocol and
+    /// install it via `pm install`, reporting byte-level progress through `on_progress` so a
+    /// large APK doesn't block with no feedback the way a plain `adb install` does. Cleans up
+    /// the pushed file afterwards regardless of outcome.
+    pub fn install_streamed_push(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        storage: AndroidStorageInput,
+        on_progress: Option<&mut crate::adb::sync::ProgressCallback>,
+    ) -> InstallResult {
+        if !std::path::Path::new(apk_path).exists() {
+            return InstallResult::failure(device_id, "APK file not found", None);
+        }
+
+        let remote_path = format!(
+            "/data/local/tmp/{}",
+            std::path::Path::new(apk_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("adbcompass_install.apk")
+        );
+
+        let result = (|| -> Result<String, String> {
+            let mut sync =
+                crate::adb::SyncClient::connect(device_id).map_err(|e| e.to_string())?;
+            sync.push_file(
+                std::path::Path::new(apk_path),
+                &remote_path,
+                0o644,
+                crate::adb::SyncCompression::None,
+                on_progress,
+            )
+            .map_err(|e| e.to_string())?;
+
+            let mut protocol =
+                crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+            let output = protocol
+                .run_device_service(device_id, &format!("shell:pm install -r '{}'", remote_path))
+                .map_err(|e| e.to_string())?;
+            let text = String::from_utf8_lossy(&output).to_string();
+
+            if text.contains("Success") {
+                Ok("APK installed successfully via native streamed push".to_string())
+            } else {
+                Err(text)
+            }
+        })();
+
+        // Best-effort cleanup; install result doesn't depend on whether this succeeds.
+        if let Ok(mut cleanup) = crate::adb::AdbProtocolClient::connect_default() {
+            let _ = cleanup.run_device_service(device_id, &format!("shell:rm -f '{}'", remote_path));
+        }
+
+        match result {
+            Ok(message) => InstallResult::success(device_id, &message)
+                .with_strategy(InstallStrategy::PushThenPmInstall, storage),
+            Err(text) => {
+                let (message, error_code) = map_install_error(&text);
+                InstallResult::failure(device_id, &message, error_code.as_deref())
+            }
+        }
+    }
+
+    /// Push the APK to a temp path on `/data/local/tmp` and install it via `pm install`,
+    /// cleaning up the pushed file afterwards regardless of outcome.
+    fn push_then_pm_install(&self, device_id: &str, apk_path: &str) -> Result<String, String> {
+        let remote_path = format!(
+            "/data/local/tmp/{}",
+            std::path::Path::new(apk_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("adbcompass_install.apk")
+        );
+
+        let mut sync = crate::adb::SyncClient::connect(device_id).map_err(|e| e.to_string())?;
+        sync.push_file(
+            std::path::Path::new(apk_path),
+            &remote_path,
+            0o644,
+            crate::adb::SyncCompression::None,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut protocol = crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        let output = protocol
+            .run_device_service(device_id, &format!("shell:pm install -r '{}'", remote_path))
+            .map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        // Best-effort cleanup; install result doesn't depend on whether this succeeds.
+        if let Ok(mut cleanup) = crate::adb::AdbProtocolClient::connect_default() {
+            let _ = cleanup.run_device_service(device_id, &format!("shell:rm -f '{}'", remote_path));
+        }
+
+        if text.contains("Success") {
+            Ok("APK installed successfully via push-then-pm-install".to_string())
+        } else {
+            Err(text)
+        }
+    }
+
+    /// Stream the APK into `package install` over `abb_exec`. If a prior install of the same
+    /// package left behind both a block-hash manifest entry and its persisted base copy, only
+    /// the blocks that actually changed are transferred and patched into a device-side copy of
+    /// that base, which is then installed; otherwise this falls back to a full streamed install
+    /// and persists the pushed copy as the base for the next install. Returns the success
+    /// message plus which strategy was actually used.
+    fn install_streamed_or_incremental(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+    ) -> Result<(String, InstallStrategy), String> {
+        let new_hashes = incremental::block_hashes(apk_path)?;
+        let manifest_key = incremental::manifest_key(device_id, apk_path);
+        let mut manifest = incremental::IncrementalManifest::load();
+        let old_hashes = manifest.entries.get(&manifest_key).cloned();
+        let base_remote_path = incremental::base_remote_path(&manifest_key);
+
+        let result = match old_hashes {
+            Some(old_hashes) if self.remote_file_exists(device_id, &base_remote_path)? => self
+                .install_incremental_patch(
+                    device_id,
+                    apk_path,
+                    &base_remote_path,
+                    &old_hashes,
+                    &new_hashes,
+                )
+                .map(|message| (message, InstallStrategy::Incremental)),
+            _ => {
+                self.install_streamed(device_id, apk_path)?;
+                self.persist_incremental_base(device_id, apk_path, &base_remote_path)?;
+                Ok((
+                    "APK installed via streamed abb_exec install".to_string(),
+                    InstallStrategy::Streamed,
+                ))
+            }
+        }?;
+
+        manifest.entries.insert(manifest_key, new_hashes);
+        manifest.save();
+
+        Ok(result)
+    }
+
+    /// Whether `remote_path` exists on `device_id`, via the sync `STAT` command.
+    fn remote_file_exists(&self, device_id: &str, remote_path: &str) -> Result<bool, String> {
+        let mut sync = crate::adb::SyncClient::connect(device_id).map_err(|e| e.to_string())?;
+        Ok(sync
+            .stat(remote_path)
+            .map(|stat| stat.exists())
+            .unwrap_or(false))
+    }
+
+    /// Push a full copy of the just-installed APK to `base_remote_path`, so the next install of
+    /// the same package has something to diff its blocks against.
+    fn persist_incremental_base(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        base_remote_path: &str,
+    ) -> Result<(), String> {
+        let mut sync = crate::adb::SyncClient::connect(device_id).map_err(|e| e.to_string())?;
+        if let Some(parent) = std::path::Path::new(base_remote_path).parent() {
+            sync.mkdir_remote(&parent.to_string_lossy())
+                .map_err(|e| e.to_string())?;
+        }
+        sync.push_file(
+            std::path::Path::new(apk_path),
+            base_remote_path,
+            0o644,
+            crate::adb::SyncCompression::None,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Reconstructs the new APK on-device by copying each unchanged block straight from the
+    /// persisted base and transferring only the blocks whose hash actually differs, then
+    /// installs the reconstructed file and leaves it in place as the base for the next install.
+    fn install_incremental_patch(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        base_remote_path: &str,
+        old_hashes: &[u64],
+        new_hashes: &[u64],
+    ) -> Result<String, String> {
+        let data = std::fs::read(apk_path).map_err(|e| e.to_string())?;
+        let mut protocol =
+            crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        let output_remote_path = format!("{}.new", base_remote_path);
+        let block_size = incremental::BLOCK_SIZE;
+
+        protocol
+            .run_device_service(device_id, &format!("shell:: > '{}'", output_remote_path))
+            .map_err(|e| e.to_string())?;
+
+        let mut changed_blocks = 0usize;
+        for (i, new_hash) in new_hashes.iter().enumerate() {
+            if old_hashes.get(i) == Some(new_hash) {
+                // Unchanged block - copy it from the persisted base instead of sending it.
+                let copy_cmd = format!(
+                    "dd if='{}' of='{}' bs={} skip={} seek={} count=1 conv=notrunc 2>/dev/null",
+                    base_remote_path, output_remote_path, block_size, i, i
+                );
+                protocol
+                    .run_device_service(device_id, &format!("shell:{}", copy_cmd))
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+
+            // Changed block - the only bytes that actually cross the wire for this install.
+            changed_blocks += 1;
+            let start = i * block_size;
+            let end = (start + block_size).min(data.len());
+
+            let block_local_path = std::env::temp_dir().join(format!(
+                "adb-compass-incremental-block-{}-{}.bin",
+                std::process::id(),
+                i
+            ));
+            std::fs::write(&block_local_path, &data[start..end]).map_err(|e| e.to_string())?;
+
+            let block_remote_path = format!("{}.block", output_remote_path);
+            let push_result = (|| -> Result<(), String> {
+                let mut sync =
+                    crate::adb::SyncClient::connect(device_id).map_err(|e| e.to_string())?;
+                sync.push_file(
+                    &block_local_path,
+                    &block_remote_path,
+                    0o644,
+                    crate::adb::SyncCompression::None,
+                    None,
+                )
+                .map_err(|e| e.to_string())?;
+                Ok(())
+            })();
+            let _ = std::fs::remove_file(&block_local_path);
+            push_result?;
+
+            let patch_cmd = format!(
+                "dd if='{}' of='{}' bs={} seek={} conv=notrunc 2>/dev/null; rm -f '{}'",
+                block_remote_path, output_remote_path, block_size, i, block_remote_path
+            );
+            protocol
+                .run_device_service(device_id, &format!("shell:{}", patch_cmd))
+                .map_err(|e| e.to_string())?;
+        }
+
+        protocol
+            .run_device_service(
+                device_id,
+                &format!("shell:mv -f '{}' '{}'", output_remote_path, base_remote_path),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let output = protocol
+            .run_device_service(device_id, &format!("shell:pm install -r '{}'", base_remote_path))
+            .map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        if text.contains("Success") {
+            Ok(format!(
+                "APK installed via incremental install ({}/{} blocks transferred)",
+                changed_blocks,
+                new_hashes.len()
+            ))
+        } else {
+            Err(text)
+        }
+    }
+
+    /// Install a split APK set (an app bundle's base + config splits) on device, auto-selecting
+    /// a storage target.
+    pub fn install_multiple(&self, device_id: &str, apk_paths: &[&str]) -> InstallResult {
+        self.install_multiple_with_storage(device_id, apk_paths, AndroidStorageInput::Auto)
+    }
+
+    /// Install a split APK set, targeting the given storage volume.
+    ///
+    /// If the device reports the `abb_exec` feature, the splits are streamed straight into a
+    /// `package install-create`/`install-write`/`install-commit` session over the abb channel.
+    /// Devices without `abb_exec` fall back to a plain `adb install-multiple -r`.
+    pub fn install_multiple_with_storage(
+        &self,
+        device_id: &str,
+        apk_paths: &[&str],
+        storage: AndroidStorageInput,
+    ) -> InstallResult {
+        if apk_paths.is_empty() {
+            return InstallResult::failure(device_id, "No APK files provided", None);
+        }
+
+        if let Some(missing) = apk_paths
+            .iter()
+            .find(|p| !std::path::Path::new(p).exists())
+        {
+            return InstallResult::failure(
+                device_id,
+                &format!("APK file not found: {}", missing),
+                None,
+            );
+        }
+
+        if crate::adb::protocol::device_features(device_id)
+            .map(|features| features.iter().any(|f| f == "abb_exec"))
+            .unwrap_or(false)
+        {
+            if self
+                .install_multiple_streamed(device_id, apk_paths)
+                .is_ok()
+            {
+                return InstallResult::success(
+                    device_id,
+                    "Split APK set installed via streamed abb_exec install session",
+                )
+                .with_strategy(InstallStrategy::MultipleStreamed, storage);
+            }
+            // Fall through to the classic path below (e.g. `abb_exec` advertised but the
+            // daemon on this ROM doesn't actually implement install sessions).
+        }
+
+        let output = self.executor.run_with_retry(
+            || {
+                let mut cmd = crate::command_utils::hidden_command(self.executor.get_adb_path());
+                cmd.args(["-s", device_id, "install-multiple", "-r"]);
+                cmd.args(apk_paths);
+                cmd
+            },
+            std::time::Duration::from_secs(180),
+            0,
+        );
+
+        match output {
+            Ok(result) => {
+                let stdout = String::from_utf8_lossy(&result.stdout);
+                let stderr = String::from_utf8_lossy(&result.stderr);
+                let combined = format!("{}{}", stdout, stderr);
+
+                if result.status.success() && combined.contains("Success") {
+                    InstallResult::success(
+                        device_id,
+                        "Split APK set installed via adb install-multiple",
+                    )
+                    .with_strategy(InstallStrategy::MultipleDirect, storage)
+                } else {
+                    let (message, error_code) = map_install_error(&combined);
+                    InstallResult::failure(device_id, &message, error_code.as_deref())
+                }
+            }
+            Err(e) => InstallResult::failure(device_id, &format!("Failed to run adb: {}", e), None),
+        }
+    }
+
+    /// Stream a split APK set into a `package` install session over `abb_exec`: open a
+    /// session with `install-create`, write each split's bytes with `install-write`, then
+    /// `install-commit` to apply them all atomically.
+    fn install_multiple_streamed(&self, device_id: &str, apk_paths: &[&str]) -> Result<(), String> {
+        let session_id = self.create_install_session(device_id)?;
+
+        for (idx, apk_path) in apk_paths.iter().enumerate() {
+            self.write_install_session(device_id, &session_id, idx, apk_path)?;
+        }
+
+        self.commit_install_session(device_id, &session_id)
+    }
+
+    /// Open an install session via `package install-create -r` and parse the session id out of
+    /// `Success: created install session [NNNN]`.
+    fn create_install_session(&self, device_id: &str) -> Result<String, String> {
+        let mut protocol =
+            crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        let output = protocol
+            .run_device_service(device_id, "abb_exec:package\0install-create\0-r")
+            .map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        parse_session_id(&text)
+            .ok_or_else(|| format!("Could not parse install session id from: {}", text.trim()))
+    }
+
+    /// Stream a single split's bytes into the open session via `install-write`.
+    fn write_install_session(
+        &self,
+        device_id: &str,
+        session_id: &str,
+        idx: usize,
+        apk_path: &str,
+    ) -> Result<(), String> {
+        let size = std::fs::metadata(apk_path).map_err(|e| e.to_string())?.len();
+        let mut file = std::fs::File::open(apk_path).map_err(|e| e.to_string())?;
+        let split_name = format!("split_{}.apk", idx);
+
+        let mut protocol =
+            crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        protocol.transport(device_id).map_err(|e| e.to_string())?;
+        protocol
+            .send_request(&format!(
+                "abb_exec:package\0install-write\0-S\0{}\0{}\0{}",
+                size, session_id, split_name
+            ))
+            .map_err(|e| e.to_string())?;
+
+        std::io::copy(&mut file, protocol.stream_mut()).map_err(|e| e.to_string())?;
+        protocol
+            .stream_mut()
+            .shutdown(std::net::Shutdown::Write)
+            .map_err(|e| e.to_string())?;
+
+        let output = protocol.read_to_end().map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        if text.contains("Success") {
+            Ok(())
+        } else {
+            Err(text)
+        }
+    }
+
+    /// Apply all writes in the session atomically via `install-commit`.
+    fn commit_install_session(&self, device_id: &str, session_id: &str) -> Result<(), String> {
+        let mut protocol =
+            crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        let output = protocol
+            .run_device_service(
+                device_id,
+                &format!("abb_exec:package\0install-commit\0{}", session_id),
+            )
+            .map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        if text.contains("Success") {
+            Ok(())
+        } else {
+            Err(text)
+        }
+    }
+
+    /// Stream the raw APK bytes into `package install` over the `abb_exec` channel
+    /// (`abb_exec:package\0install\0-r\0-S\0<size>`), avoiding the temp-copy-then-`pm install`
+    /// round trip that `push_then_pm_install` needs.
+    fn install_streamed(&self, device_id: &str, apk_path: &str) -> Result<(), String> {
+        let size = std::fs::metadata(apk_path).map_err(|e| e.to_string())?.len();
+        let mut file = std::fs::File::open(apk_path).map_err(|e| e.to_string())?;
+
+        let mut protocol =
+            crate::adb::AdbProtocolClient::connect_default().map_err(|e| e.to_string())?;
+        protocol.transport(device_id).map_err(|e| e.to_string())?;
+        protocol
+            .send_request(&format!("abb_exec:package\0install\0-r\0-S\0{}", size))
+            .map_err(|e| e.to_string())?;
+
+        std::io::copy(&mut file, protocol.stream_mut()).map_err(|e| e.to_string())?;
+        protocol
+            .stream_mut()
+            .shutdown(std::net::Shutdown::Write)
+            .map_err(|e| e.to_string())?;
+
+        let output = protocol.read_to_end().map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output).to_string();
+
+        if text.contains("Success") {
+            Ok(())
+        } else {
+            Err(text)
+        }
+    }
+}
+
+/// Parse the session id out of `pm install-create`'s `Success: created install session [NNNN]`.
+fn parse_session_id(output: &str) -> Option<String> {
+    let start = output.find('[')? + 1;
+    let end = output[start..].find(']')? + start;
+    Some(output[start..end].to_string())
+}
+
+/// Block-hash manifest backing incremental (fastdeploy-style) installs.
+///
+/// Each entry's hashes are diffed against a freshly-hashed APK to find which blocks actually
+/// changed since the last install of that package; `ApkInstaller::install_incremental_patch`
+/// uses that diff to patch only those blocks into a persisted on-device base copy instead of
+/// transferring the whole file again.
+mod incremental {
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::hash::{Hash, Hasher};
+    use std::path::{Path, PathBuf};
+
+    pub(super) const BLOCK_SIZE: usize = 64 * 1024;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+    pub(super) struct IncrementalManifest {
+        pub(super) entries: HashMap<String, Vec<u64>>,
+    }
+
+    impl IncrementalManifest {
+        fn path() -> PathBuf {
+            std::env::temp_dir().join("adb-compass-incremental-manifest.json")
+        }
+
+        pub(super) fn load() -> Self {
+            std::fs::read_to_string(Self::path())
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default()
+        }
+
+        pub(super) fn save(&self) {
+            if let Ok(json) = serde_json::to_string(self) {
+                let _ = std::fs::write(Self::path(), json);
+            }
+        }
+    }
+
+    /// Key a manifest entry by device + APK file name. Neither the package name nor the signing
+    /// certificate is part of the key, so different APKs pushed under the same file name to the
+    /// same device share one entry.
+    pub(super) fn manifest_key(device_id: &str, apk_path: &str) -> String {
+        let file_name = Path::new(apk_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(apk_path);
+        format!("{}:{}", device_id, file_name)
+    }
+
+    /// Where a manifest entry's persisted base copy lives on-device, so a later install of the
+    /// same package has bytes to diff and patch against instead of just hashes.
+    pub(super) fn base_remote_path(manifest_key: &str) -> String {
+        let safe_key: String = manifest_key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("/data/local/tmp/.adbcompass_incremental/{}.apk", safe_key)
+    }
+
+    pub(super) fn block_hashes(apk_path: &str) -> Result<Vec<u64>, String> {
+        let data = std::fs::read(apk_path).map_err(|e| e.to_string())?;
+        Ok(data.chunks(BLOCK_SIZE).map(hash_block).collect())
+    }
+
+    fn hash_block(block: &[u8]) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        block.hash(&mut hasher);
+        hasher.finish()
+    }
+}
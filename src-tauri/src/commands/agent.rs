@@ -1,149 +1,123 @@
-use crate::adb::{AdbExecutor, AgentManager};
+use crate::adb::AgentManager;
 use serde_json::json;
+use tauri::State;
 
 #[tauri::command]
-pub async fn test_agent_connection(device_id: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    // 1. Start agent
-    manager
-        .start_agent(&device_id)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // 2. Ping agent
-    let response = manager
+pub async fn test_agent_connection(
+    state: State<'_, AgentManager>,
+    device_id: String,
+) -> Result<serde_json::Value, String> {
+    // `send_command` starts the agent and establishes the pooled connection itself when one
+    // doesn't already exist, so a bare PING is enough to exercise the whole path.
+    state
         .send_command(&device_id, "PING", json!({}))
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_apps_full(
+    state: State<'_, AgentManager>,
     device_id: String,
     include_system: bool,
 ) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+    state
         .get_apps_full(&device_id, include_system)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_app_icon(device_id: String, package: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn get_app_icon(
+    state: State<'_, AgentManager>,
+    device_id: String,
+    package: String,
+) -> Result<serde_json::Value, String> {
+    state
         .get_app_icon(&device_id, &package)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn list_files_fast(device_id: String, path: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn list_files_fast(
+    state: State<'_, AgentManager>,
+    device_id: String,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    state
         .list_files_fast(&device_id, &path)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_performance_stats(device_id: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn get_performance_stats(
+    state: State<'_, AgentManager>,
+    device_id: String,
+) -> Result<serde_json::Value, String> {
+    state
         .get_performance_stats(&device_id)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_clipboard(device_id: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn get_clipboard(
+    state: State<'_, AgentManager>,
+    device_id: String,
+) -> Result<serde_json::Value, String> {
+    state
         .get_clipboard(&device_id)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn set_clipboard(device_id: String, text: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn set_clipboard(
+    state: State<'_, AgentManager>,
+    device_id: String,
+    text: String,
+) -> Result<serde_json::Value, String> {
+    state
         .set_clipboard(&device_id, &text)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn inject_tap_fast(
+    state: State<'_, AgentManager>,
     device_id: String,
     x: i32,
     y: i32,
 ) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+    state
         .inject_tap(&device_id, x, y)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn build_index(device_id: String, path: String) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+pub async fn build_index(
+    state: State<'_, AgentManager>,
+    device_id: String,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    state
         .build_index(&device_id, &path)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn search_files_fast(
+    state: State<'_, AgentManager>,
     device_id: String,
     query: String,
 ) -> Result<serde_json::Value, String> {
-    let executor = AdbExecutor::new();
-    let manager = AgentManager::new(executor);
-
-    let response = manager
+    state
         .search_files_fast(&device_id, &query)
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(response)
+        .map_err(|e| e.to_string())
 }
@@ -2,7 +2,19 @@
 // Handles APK validation and installation
 
 use crate::adb::AdbExecutor;
-use crate::apk::{ApkInfo, InstallResult};
+use crate::apk::{AndroidStorageInput, ApkInfo, InstallResult};
+use crate::requirements::DeviceRequirements;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Progress payload emitted while a large APK is pushed over the native sync protocol ahead
+/// of a `pm install`.
+#[derive(Clone, Serialize)]
+pub struct InstallProgressPayload {
+    pub device_id: String,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+}
 
 /// Validate APK file and return info
 #[tauri::command]
@@ -10,11 +22,68 @@ pub fn validate_apk(path: String) -> Option<ApkInfo> {
     ApkInfo::from_path(&path)
 }
 
-/// Install APK on a specific device
+/// Install APK on a specific device, optionally targeting a storage volume
+#[tauri::command]
+pub fn install_apk(
+    device_id: String,
+    apk_path: String,
+    storage: Option<AndroidStorageInput>,
+) -> InstallResult {
+    let executor = AdbExecutor::new();
+    executor.install_apk_with_storage(&device_id, &apk_path, storage.unwrap_or_default())
+}
+
+/// Install an APK by pushing it over the native sync protocol (in ≤64 KiB chunks) and running
+/// `pm install`, emitting `install-progress` events so a large APK shows a real progress bar
+/// instead of blocking on an opaque `adb install`.
+#[tauri::command]
+pub fn install_apk_streamed(
+    app_handle: AppHandle,
+    device_id: String,
+    apk_path: String,
+    storage: Option<AndroidStorageInput>,
+) -> InstallResult {
+    let executor = AdbExecutor::new();
+    let device_id_for_progress = device_id.clone();
+    let mut on_progress = move |bytes_sent: u64, total_bytes: u64, _path: &str| {
+        let _ = app_handle.emit(
+            "install-progress",
+            InstallProgressPayload {
+                device_id: device_id_for_progress.clone(),
+                bytes_sent,
+                total_bytes,
+            },
+        );
+    };
+
+    executor.install_apk_streamed_push(
+        &device_id,
+        &apk_path,
+        storage.unwrap_or_default(),
+        Some(&mut on_progress),
+    )
+}
+
+/// Install a split APK set (app bundle base + config splits) on a specific device, optionally
+/// targeting a storage volume
+#[tauri::command]
+pub fn install_apk_split(
+    device_id: String,
+    apk_paths: Vec<String>,
+    storage: Option<AndroidStorageInput>,
+) -> InstallResult {
+    let executor = AdbExecutor::new();
+    let paths: Vec<&str> = apk_paths.iter().map(|s| s.as_str()).collect();
+    executor.install_apk_multiple(&device_id, &paths, storage.unwrap_or_default())
+}
+
+/// Check whether an APK is compatible with a device (SDK version, ABI) before installing
 #[tauri::command]
-pub fn install_apk(device_id: String, apk_path: String) -> InstallResult {
+pub fn check_apk_compatibility(device_id: String, path: String) -> Option<DeviceRequirements> {
+    let info = ApkInfo::from_path(&path)?;
     let executor = AdbExecutor::new();
-    executor.install_apk(&device_id, &apk_path)
+    let checks = executor.check_apk_compatibility(&device_id, &info);
+    Some(DeviceRequirements::new(&device_id, checks))
 }
 
 /// Scan a folder for APK files
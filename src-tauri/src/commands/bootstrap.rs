@@ -0,0 +1,20 @@
+// Bootstrap Commands - Tauri command for auto-provisioning platform-tools on a clean machine
+// where no working `adb` can be found.
+
+use crate::adb::{ensure_adb, BootstrapProgress};
+use crate::error::AppError;
+use tauri::{AppHandle, Emitter};
+
+/// Ensure a working `adb` is installed, downloading and extracting Google's official
+/// platform-tools archive if needed. Emits `adb-bootstrap-progress` events while it works.
+/// Pass `force` to re-download even if a managed copy already exists (e.g. after a corrupt
+/// extraction).
+#[tauri::command]
+pub fn ensure_adb_available(app_handle: AppHandle, force: bool) -> Result<String, AppError> {
+    let mut on_progress = move |progress: &BootstrapProgress| {
+        let _ = app_handle.emit("adb-bootstrap-progress", progress.clone());
+    };
+
+    let path = ensure_adb(force, &mut on_progress)?;
+    Ok(path.to_string_lossy().to_string())
+}
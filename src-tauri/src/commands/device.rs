@@ -1,9 +1,10 @@
 // Device Commands - Tauri commands for device management
 // Handles device detection, status checking, and basic operations
 
-use crate::adb::{executor::DeviceInfo, AdbExecutor};
+use crate::adb::{executor::DeviceInfo, usb_monitor, AdbExecutor, DeviceMonitorState};
 use crate::error::AppError;
 use serde::Serialize;
+use tauri::{AppHandle, State};
 
 /// Response for ADB status check
 #[derive(Serialize)]
@@ -92,3 +93,17 @@ pub fn check_action_requirements(device_id: String) -> Vec<crate::requirements::
     let executor = AdbExecutor::new();
     executor.check_action_requirements(&device_id)
 }
+
+/// Start pushing `device-arrived`/`device-departed` events as devices are plugged in or
+/// unplugged, instead of the frontend polling `get_devices`/`refresh_devices`. A no-op if
+/// already running.
+#[tauri::command]
+pub fn start_device_monitor(app_handle: AppHandle, state: State<'_, DeviceMonitorState>) {
+    usb_monitor::start(app_handle, &state);
+}
+
+/// Stop the USB device monitor started by `start_device_monitor`.
+#[tauri::command]
+pub fn stop_device_monitor(state: State<'_, DeviceMonitorState>) {
+    usb_monitor::stop(&state);
+}
@@ -6,6 +6,7 @@ use crate::command_utils::hidden_command;
 use crate::error::AppError;
 use serde::Serialize;
 use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Serialize)]
 pub struct DeviceProps {
@@ -47,6 +48,37 @@ pub fn reboot_device(device_id: String, mode: Option<String>) -> Result<(), AppE
     Ok(())
 }
 
+/// Progress payload emitted while an OTA/recovery package is sideloaded.
+#[derive(Clone, Serialize)]
+pub struct SideloadProgressPayload {
+    pub device_id: String,
+    pub blocks_served: u32,
+    pub total_blocks: u32,
+}
+
+/// Flash an OTA/recovery package to a device that is already in sideload mode, using the
+/// native sideload-host protocol so progress can be reported to the UI.
+#[tauri::command]
+pub fn sideload_package(
+    app_handle: AppHandle,
+    device_id: String,
+    zip_path: String,
+) -> Result<(), AppError> {
+    let device_id_for_progress = device_id.clone();
+    let mut on_progress = move |blocks_served: u32, total_blocks: u32| {
+        let _ = app_handle.emit(
+            "sideload-progress",
+            SideloadProgressPayload {
+                device_id: device_id_for_progress.clone(),
+                blocks_served,
+                total_blocks,
+            },
+        );
+    };
+
+    crate::adb::sideload::sideload(&device_id, &zip_path, Some(&mut on_progress))
+}
+
 /// Input text to device's current focused input
 #[tauri::command]
 pub fn input_text(device_id: String, text: String) -> Result<(), AppError> {
@@ -294,6 +326,156 @@ pub fn get_device_props(device_id: String) -> Result<DeviceProps, AppError> {
     })
 }
 
+/// Full health-HAL battery readout, beyond the plain level/charging flag in [`DeviceProps`].
+#[derive(Debug, Serialize)]
+pub struct BatteryHealth {
+    pub level: Option<u8>,
+    pub scale: Option<u8>,
+    pub is_charging: bool,
+    /// Degrees Celsius, parsed from the `temperature:` field which `dumpsys battery` reports
+    /// in tenths of a degree.
+    pub temperature_celsius: Option<f32>,
+    pub voltage_mv: Option<u32>,
+    /// `dumpsys battery`'s `health:` enum, mapped to a readable name (e.g. `Good`, `Overheat`).
+    pub health: Option<String>,
+    pub technology: Option<String>,
+    pub charge_counter_uah: Option<u32>,
+    /// Estimated remaining capacity in mAh, derived from the charge counter where available.
+    pub remaining_mah: Option<u32>,
+    pub cycle_count: Option<u32>,
+    /// `charge_full / charge_full_design`, i.e. how much of the original design capacity
+    /// remains, where the kernel exposes both.
+    pub wear_percent: Option<f32>,
+}
+
+/// Android's `health:` enum values from `BatteryManager`, in the order `dumpsys battery` prints
+/// the underlying integer status for; that integer starts at 1 (`Unknown`), so entry `n - 1`
+/// names status `n`.
+const BATTERY_HEALTH_NAMES: &[&str] = &[
+    "Unknown",
+    "Good",
+    "Overheat",
+    "Dead",
+    "Over voltage",
+    "Unspecified failure",
+    "Cold",
+];
+
+/// Get a rich battery/thermal health readout from the health-HAL fields `dumpsys battery`
+/// exposes, falling back to `/sys/class/power_supply/battery` for cycle count and wear when
+/// `dumpsys` doesn't report them.
+#[tauri::command]
+pub fn get_battery_health(device_id: String) -> Result<BatteryHealth, AppError> {
+    let executor = AdbExecutor::new();
+    let adb_path = executor.get_adb_path();
+
+    let output = hidden_command(adb_path)
+        .args(["-s", &device_id, "shell", "dumpsys", "battery"])
+        .output()
+        .map_err(|e| {
+            AppError::new(
+                "BATTERY_HEALTH_FAILED",
+                &format!("Failed to read battery health: {}", e),
+            )
+        })?;
+
+    let dump = String::from_utf8_lossy(&output.stdout);
+
+    let level = dumpsys_field(&dump, "level").and_then(|v| v.parse::<u8>().ok());
+    let scale = dumpsys_field(&dump, "scale").and_then(|v| v.parse::<u8>().ok());
+
+    let is_charging = dump.contains("USB powered: true")
+        || dump.contains("AC powered: true")
+        || dump.contains("Wireless powered: true");
+
+    let temperature_celsius = dumpsys_field(&dump, "temperature")
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|tenths| tenths / 10.0);
+
+    let voltage_mv = dumpsys_field(&dump, "voltage").and_then(|v| v.parse::<u32>().ok());
+
+    let health = dumpsys_field(&dump, "health")
+        .and_then(|v| v.parse::<usize>().ok())
+        .and_then(|status| BATTERY_HEALTH_NAMES.get(status.checked_sub(1)?))
+        .map(|s| s.to_string());
+
+    let technology = dumpsys_field(&dump, "technology");
+
+    let charge_counter_uah = dumpsys_field(&dump, "Charge counter")
+        .or_else(|| dumpsys_field(&dump, "charge counter"))
+        .and_then(|v| v.parse::<u32>().ok());
+    let remaining_mah = charge_counter_uah.map(|uah| uah / 1000);
+
+    let mut cycle_count = dumpsys_field(&dump, "Cycle count")
+        .or_else(|| dumpsys_field(&dump, "cycle count"))
+        .and_then(|v| v.parse::<u32>().ok());
+
+    let mut wear_percent = None;
+
+    if cycle_count.is_none() {
+        if let Ok(raw) = read_power_supply_file(adb_path, &device_id, "cycle_count") {
+            cycle_count = raw.parse::<u32>().ok();
+        }
+    }
+
+    if let (Ok(full), Ok(design)) = (
+        read_power_supply_file(adb_path, &device_id, "charge_full"),
+        read_power_supply_file(adb_path, &device_id, "charge_full_design"),
+    ) {
+        if let (Ok(full), Ok(design)) = (full.parse::<f32>(), design.parse::<f32>()) {
+            if design > 0.0 {
+                wear_percent = Some((full / design) * 100.0);
+            }
+        }
+    }
+
+    Ok(BatteryHealth {
+        level,
+        scale,
+        is_charging,
+        temperature_celsius,
+        voltage_mv,
+        health,
+        technology,
+        charge_counter_uah,
+        remaining_mah,
+        cycle_count,
+        wear_percent,
+    })
+}
+
+/// Read a single value out of `/sys/class/power_supply/battery/<file>`, for the health fields
+/// `dumpsys battery` doesn't report on every device.
+fn read_power_supply_file(
+    adb_path: &std::path::Path,
+    device_id: &str,
+    file: &str,
+) -> Result<String, AppError> {
+    let path = format!("/sys/class/power_supply/battery/{}", file);
+    let output = hidden_command(adb_path)
+        .args(["-s", device_id, "shell", "cat", &path])
+        .output()
+        .map_err(|e| AppError::new("BATTERY_HEALTH_FAILED", &format!("Failed to read {}: {}", path, e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::new(
+            "BATTERY_HEALTH_FAILED",
+            &format!("{} not present on device", path),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse a `<key>: <value>` line out of `dumpsys battery` output.
+fn dumpsys_field(dump: &str, key: &str) -> Option<String> {
+    let prefix = format!("{}:", key);
+    dump.lines()
+        .find(|l| l.trim().starts_with(&prefix))
+        .and_then(|l| l.split_once(':'))
+        .map(|(_, v)| v.trim().to_string())
+}
+
 fn format_storage_size(s: &str) -> String {
     if let Ok(kb) = s.parse::<u64>() {
         if kb >= 1024 * 1024 {
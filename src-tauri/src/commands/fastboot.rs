@@ -0,0 +1,86 @@
+// Fastboot Commands - Tauri commands for managing devices in the bootloader
+// Analogous to `device_actions.rs`, but targets `FastbootExecutor` instead of `AdbExecutor`
+// since a device rebooted into the bootloader no longer shows up in `adb devices`.
+
+use crate::error::AppError;
+use crate::fastboot::{FastbootDeviceInfo, FastbootExecutor};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Get list of devices currently sitting in the bootloader.
+#[tauri::command]
+pub fn get_fastboot_devices() -> Result<Vec<FastbootDeviceInfo>, AppError> {
+    let executor = FastbootExecutor::new();
+    executor.list_devices()
+}
+
+/// Read a single `getvar` value off a fastboot device, e.g. `product`, `current-slot`, or
+/// `unlocked`.
+#[tauri::command]
+pub fn fastboot_getvar(serial: String, name: String) -> Result<String, AppError> {
+    let executor = FastbootExecutor::new();
+    executor.get_var(&serial, &name)
+}
+
+/// Erase a partition.
+#[tauri::command]
+pub fn fastboot_erase(serial: String, partition: String) -> Result<(), AppError> {
+    let executor = FastbootExecutor::new();
+    executor.erase(&serial, &partition)
+}
+
+/// Temporarily boot an image without flashing it.
+#[tauri::command]
+pub fn fastboot_boot(serial: String, image_path: String) -> Result<(), AppError> {
+    let executor = FastbootExecutor::new();
+    executor.boot(&serial, &image_path)
+}
+
+/// Set the active A/B slot.
+#[tauri::command]
+pub fn fastboot_set_active(serial: String, slot: String) -> Result<(), AppError> {
+    let executor = FastbootExecutor::new();
+    executor.set_active(&serial, &slot)
+}
+
+/// Reboot the device out of the bootloader, or into a specific mode.
+#[tauri::command]
+pub fn fastboot_reboot(serial: String, mode: Option<String>) -> Result<(), AppError> {
+    let executor = FastbootExecutor::new();
+    executor.reboot(&serial, mode.as_deref())
+}
+
+/// Progress payload emitted while a partition image is flashed.
+#[derive(Clone, Serialize)]
+pub struct FastbootFlashProgressPayload {
+    pub serial: String,
+    pub partition: String,
+    pub status_line: String,
+}
+
+/// Flash an image to a partition, emitting `fastboot-flash-progress` events with each status
+/// line fastboot reports while it transfers and writes the image.
+#[tauri::command]
+pub fn fastboot_flash(
+    app_handle: AppHandle,
+    serial: String,
+    partition: String,
+    image_path: String,
+) -> Result<(), AppError> {
+    let executor = FastbootExecutor::new();
+
+    let serial_for_progress = serial.clone();
+    let partition_for_progress = partition.clone();
+    let mut on_progress = move |status_line: &str| {
+        let _ = app_handle.emit(
+            "fastboot-flash-progress",
+            FastbootFlashProgressPayload {
+                serial: serial_for_progress.clone(),
+                partition: partition_for_progress.clone(),
+                status_line: status_line.to_string(),
+            },
+        );
+    };
+
+    executor.flash(&serial, &partition, &image_path, Some(&mut on_progress))
+}
@@ -1,10 +1,12 @@
 // File Transfer Commands - Push, pull, list files on device
 // Provides file management capabilities via ADB
 
-use crate::adb::AdbExecutor;
+use crate::adb::command_builder::quote_shell_arg;
+use crate::adb::{AdbExecutor, AndroidStorage, SyncClient, SyncCompression, SyncDirEntry};
 use crate::command_utils::hidden_command;
 use crate::error::AppError;
 use serde::Serialize;
+use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Serialize)]
 pub struct FileInfo {
@@ -14,140 +16,240 @@ pub struct FileInfo {
     pub permissions: Option<String>,
 }
 
-/// List files in a directory on the device
+/// List files in a directory on the device via the native sync protocol's `LIST` command,
+/// instead of shelling out to `ls -la` and splitting its output on whitespace (which breaks on
+/// filenames containing spaces and gives no structured mode/size/mtime).
 #[tauri::command]
 pub fn list_files(device_id: String, path: String) -> Result<Vec<FileInfo>, AppError> {
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
-
-    // Use ls -la to get detailed file listing
-    let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "shell", "ls", "-la", &path])
-        .output()
-        .map_err(|e| AppError::new("LIST_FILES_FAILED", &format!("Failed to list files: {}", e)))?;
+    let mut client = SyncClient::connect(&device_id)?;
+    let entries = client.list(&path)?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::new(
-            "LIST_FILES_FAILED",
-            &format!("List files failed: {}", stderr),
-        ));
-    }
+    let mut files: Vec<FileInfo> = entries.iter().map(dir_entry_to_file_info).collect();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let files = parse_ls_output(&stdout);
+    files.sort_by(|a, b| match (a.is_directory, b.is_directory) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
 
     Ok(files)
 }
 
-fn parse_ls_output(output: &str) -> Vec<FileInfo> {
-    let mut files = Vec::new();
+/// Render a sync `DENT` entry's mode bits as an `ls`-style permission string (e.g. `drwxr-xr-x`).
+fn dir_entry_to_file_info(entry: &SyncDirEntry) -> FileInfo {
+    let is_directory = entry.is_directory();
+    let permissions = format_permissions(entry.mode, is_directory);
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("total") {
-            continue;
-        }
+    FileInfo {
+        name: entry.name.clone(),
+        is_directory,
+        size: if is_directory { None } else { Some(entry.size as u64) },
+        permissions: Some(permissions),
+    }
+}
 
-        // Format: drwxrwxrwx user group size date time name
-        // or: -rw-r--r-- user group size date time name
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 7 {
-            continue;
-        }
+fn format_permissions(mode: u32, is_directory: bool) -> String {
+    let bits = [
+        (0o400, 'r'), (0o200, 'w'), (0o100, 'x'),
+        (0o040, 'r'), (0o020, 'w'), (0o010, 'x'),
+        (0o004, 'r'), (0o002, 'w'), (0o001, 'x'),
+    ];
+
+    let mut perms = String::with_capacity(10);
+    perms.push(if is_directory { 'd' } else { '-' });
+    for (mask, ch) in bits {
+        perms.push(if mode & mask != 0 { ch } else { '-' });
+    }
+    perms
+}
 
-        let permissions = parts[0];
-        let is_directory = permissions.starts_with('d');
-        let size: Option<u64> = parts[4].parse().ok();
-
-        // Name is everything after the date/time (parts 5, 6)
-        // Handle names with spaces by joining remaining parts
-        let name = if parts.len() > 7 {
-            parts[7..].join(" ")
-        } else if parts.len() == 7 {
-            parts[6].to_string()
-        } else {
-            continue;
-        };
+/// Progress payload emitted while a native sync transfer runs.
+#[derive(Clone, Serialize)]
+pub struct TransferProgressPayload {
+    pub path: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+}
 
-        // Skip . and .. entries
-        if name == "." || name == ".." {
-            continue;
-        }
+/// Event emitted once a `push_file`/`pull_file` transfer finishes successfully.
+#[derive(Clone, Serialize)]
+pub struct TransferCompletePayload {
+    pub path: String,
+    pub total_bytes: u64,
+}
 
-        // Handle symlinks: remove " -> target" part
-        let name = name.split(" -> ").next().unwrap_or(&name).to_string();
+/// Event emitted when a `push_file`/`pull_file` transfer fails partway through.
+#[derive(Clone, Serialize)]
+pub struct TransferErrorPayload {
+    pub path: String,
+    pub message: String,
+}
 
-        files.push(FileInfo {
-            name,
-            is_directory,
-            size: if is_directory { None } else { size },
-            permissions: Some(permissions.to_string()),
-        });
+/// Result of a completed `push_file`/`pull_file` transfer, reporting the codec actually used so
+/// callers can tell whether the requested compression was applied.
+#[derive(Clone, Serialize)]
+pub struct FileTransferResult {
+    pub message: String,
+    pub codec: SyncCompression,
+}
+
+fn codec_for(compress: bool) -> SyncCompression {
+    if compress {
+        SyncCompression::Lz4
+    } else {
+        SyncCompression::None
     }
+}
 
-    // Sort: directories first, then by name
-    files.sort_by(|a, b| match (a.is_directory, b.is_directory) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+/// The source file's permission bits, to preserve through `push_dir` instead of forcing every
+/// pushed file to `DEFAULT_PUSH_MODE`. Windows has no equivalent notion, so it falls back to
+/// the default there.
+#[cfg(unix)]
+fn source_file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
 
-    files
+#[cfg(not(unix))]
+fn source_file_mode(_metadata: &std::fs::Metadata) -> u32 {
+    crate::adb::sync::DEFAULT_PUSH_MODE
 }
 
-/// Push a file from local to device
+/// Resolve a (possibly relative) path against a logical storage location, so callers don't
+/// need to hard-code mount points like `/sdcard` that vary across devices.
+#[tauri::command]
+pub fn resolve_storage_path(
+    device_id: String,
+    storage: AndroidStorage,
+    relative: String,
+) -> Result<String, AppError> {
+    storage.resolve_path(&device_id, &relative)
+}
+
+/// Push a file from local to device over the native sync protocol, emitting `transfer-progress`
+/// events per ≤64KB chunk (so the UI can show a progress bar on large files instead of blocking
+/// until the whole transfer finishes) followed by `transfer-complete` or `transfer-error`. When
+/// `compress` is set, each chunk is sent as its own LZ4 frame to speed up transfers over slow
+/// USB/Wi-Fi links. A relative `remote_path` is resolved against `storage` (default `Auto`).
 #[tauri::command]
 pub fn push_file(
+    app_handle: AppHandle,
     device_id: String,
     local_path: String,
     remote_path: String,
-) -> Result<String, AppError> {
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
-
-    let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "push", &local_path, &remote_path])
-        .output()
-        .map_err(|e| AppError::new("PUSH_FAILED", &format!("Failed to push file: {}", e)))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::new(
-            "PUSH_FAILED",
-            &format!("Push failed: {}", stderr),
-        ));
+    storage: Option<AndroidStorage>,
+    compress: bool,
+) -> Result<FileTransferResult, AppError> {
+    let remote_path = storage.unwrap_or_default().resolve_path(&device_id, &remote_path)?;
+    let mut client = SyncClient::connect(&device_id)?;
+    let mut total_bytes = 0u64;
+    let mut on_progress = |bytes: u64, total: u64, path: &str| {
+        total_bytes = total;
+        let _ = app_handle.emit(
+            "transfer-progress",
+            TransferProgressPayload {
+                path: path.to_string(),
+                bytes_transferred: bytes,
+                total_bytes: total,
+            },
+        );
+    };
+
+    let result = client.push_file(
+        std::path::Path::new(&local_path),
+        &remote_path,
+        crate::adb::sync::DEFAULT_PUSH_MODE,
+        codec_for(compress),
+        Some(&mut on_progress),
+    );
+
+    match result {
+        Ok(codec) => {
+            let _ = app_handle.emit(
+                "transfer-complete",
+                TransferCompletePayload {
+                    path: remote_path.clone(),
+                    total_bytes,
+                },
+            );
+            Ok(FileTransferResult {
+                message: format!("Pushed {} to {}", local_path, remote_path),
+                codec,
+            })
+        }
+        Err(e) => {
+            let _ = app_handle.emit(
+                "transfer-error",
+                TransferErrorPayload {
+                    path: remote_path,
+                    message: e.message.clone(),
+                },
+            );
+            Err(e)
+        }
     }
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(stdout)
 }
 
-/// Pull a file from device to local
+/// Pull a file from device to local over the native sync protocol, emitting `transfer-progress`
+/// events per ≤64KB chunk followed by `transfer-complete` or `transfer-error`. When `compress`
+/// is set, chunks are expected as LZ4 frames and decompressed as they arrive. A relative
+/// `remote_path` is resolved against `storage` (default `Auto`).
 #[tauri::command]
 pub fn pull_file(
+    app_handle: AppHandle,
     device_id: String,
     remote_path: String,
     local_path: String,
-) -> Result<String, AppError> {
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
-
-    let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "pull", &remote_path, &local_path])
-        .output()
-        .map_err(|e| AppError::new("PULL_FAILED", &format!("Failed to pull file: {}", e)))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::new(
-            "PULL_FAILED",
-            &format!("Pull failed: {}", stderr),
-        ));
+    storage: Option<AndroidStorage>,
+    compress: bool,
+) -> Result<FileTransferResult, AppError> {
+    let remote_path = storage.unwrap_or_default().resolve_path(&device_id, &remote_path)?;
+    let mut client = SyncClient::connect(&device_id)?;
+    let mut total_bytes = 0u64;
+    let mut on_progress = |bytes: u64, total: u64, path: &str| {
+        total_bytes = total;
+        let _ = app_handle.emit(
+            "transfer-progress",
+            TransferProgressPayload {
+                path: path.to_string(),
+                bytes_transferred: bytes,
+                total_bytes: total,
+            },
+        );
+    };
+
+    let result = client.pull_file(
+        &remote_path,
+        std::path::Path::new(&local_path),
+        codec_for(compress),
+        Some(&mut on_progress),
+    );
+
+    match result {
+        Ok(codec) => {
+            let _ = app_handle.emit(
+                "transfer-complete",
+                TransferCompletePayload {
+                    path: remote_path.clone(),
+                    total_bytes,
+                },
+            );
+            Ok(FileTransferResult {
+                message: format!("Pulled {} to {}", remote_path, local_path),
+                codec,
+            })
+        }
+        Err(e) => {
+            let _ = app_handle.emit(
+                "transfer-error",
+                TransferErrorPayload {
+                    path: remote_path,
+                    message: e.message.clone(),
+                },
+            );
+            Err(e)
+        }
     }
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(stdout)
 }
 
 /// Delete a file or directory on device
@@ -158,7 +260,14 @@ pub fn delete_remote_file(device_id: String, remote_path: String) -> Result<(),
 
     // Try rm -rf to handle both files and directories
     let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "shell", "rm", "-rf", &remote_path])
+        .args([
+            "-s",
+            &device_id,
+            "shell",
+            "rm",
+            "-rf",
+            &quote_shell_arg(&remote_path),
+        ])
         .output()
         .map_err(|e| AppError::new("DELETE_FAILED", &format!("Failed to delete: {}", e)))?;
 
@@ -173,6 +282,245 @@ pub fn delete_remote_file(device_id: String, remote_path: String) -> Result<(),
     Ok(())
 }
 
+/// A single file that failed to transfer during a `push_dir`/`pull_dir` run.
+#[derive(Clone, Serialize)]
+pub struct DirTransferError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Summary of a recursive directory transfer. A per-file failure is recorded in `errors`
+/// instead of aborting the whole operation, so one bad file doesn't sink the rest of the tree.
+#[derive(Clone, Serialize)]
+pub struct DirTransferSummary {
+    pub files_transferred: usize,
+    pub total_bytes: u64,
+    pub errors: Vec<DirTransferError>,
+    pub codec: SyncCompression,
+}
+
+/// Push a local directory tree to a remote directory using the native sync protocol,
+/// recreating the directory structure remotely and emitting `transfer-progress` events as
+/// chunks are sent. Continues past per-file failures, reporting them in the returned summary.
+/// When `compress` is set, each file's chunks are sent as LZ4 frames.
+#[tauri::command]
+pub fn push_dir(
+    app_handle: AppHandle,
+    device_id: String,
+    local_dir: String,
+    remote_dir: String,
+    compress: bool,
+) -> Result<DirTransferSummary, AppError> {
+    let mut client = SyncClient::connect(&device_id)?;
+    let local_root = std::path::Path::new(&local_dir);
+    let remote_root = remote_dir.trim_end_matches('/');
+    let codec = codec_for(compress);
+
+    let mut files_transferred = 0usize;
+    let mut total_bytes = 0u64;
+    let mut errors = Vec::new();
+
+    for entry in walkdir::WalkDir::new(local_root)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let rel = entry
+            .path()
+            .strip_prefix(local_root)
+            .unwrap_or(entry.path());
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+
+        let remote_path = format!("{}/{}", remote_root, rel.to_string_lossy().replace('\\', "/"));
+
+        if entry.file_type().is_dir() {
+            if let Err(e) = client.mkdir_remote(&remote_path) {
+                errors.push(DirTransferError {
+                    path: remote_path,
+                    message: e.message,
+                });
+            }
+            continue;
+        }
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if let Some(parent) = std::path::Path::new(&remote_path).parent() {
+            if let Err(e) = client.mkdir_remote(&parent.to_string_lossy()) {
+                errors.push(DirTransferError {
+                    path: remote_path.clone(),
+                    message: e.message,
+                });
+                continue;
+            }
+        }
+
+        let metadata = entry.metadata().ok();
+        let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        let mode = metadata
+            .as_ref()
+            .map(source_file_mode)
+            .unwrap_or(crate::adb::sync::DEFAULT_PUSH_MODE);
+
+        let mut on_progress = |bytes: u64, total: u64, path: &str| {
+            let _ = app_handle.emit(
+                "transfer-progress",
+                TransferProgressPayload {
+                    path: path.to_string(),
+                    bytes_transferred: bytes,
+                    total_bytes: total,
+                },
+            );
+        };
+
+        match client.push_file(entry.path(), &remote_path, mode, codec, Some(&mut on_progress)) {
+            Ok(_) => {
+                files_transferred += 1;
+                total_bytes += size;
+            }
+            Err(e) => errors.push(DirTransferError {
+                path: remote_path,
+                message: e.message,
+            }),
+        }
+    }
+
+    Ok(DirTransferSummary {
+        files_transferred,
+        total_bytes,
+        errors,
+        codec,
+    })
+}
+
+/// Recursively list a remote directory tree via the sync `LIST` command, returning
+/// `(remote_path, size)` for every entry that is not a directory (symlinks and special files
+/// included, not only regular files).
+fn collect_remote_files(
+    client: &mut SyncClient,
+    remote_dir: &str,
+) -> Result<Vec<(String, u64)>, AppError> {
+    let mut files = Vec::new();
+    let mut stack = vec![remote_dir.trim_end_matches('/').to_string()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in client.list(&dir)? {
+            let path = format!("{}/{}", dir, entry.name);
+            if entry.is_directory() {
+                stack.push(path);
+            } else {
+                files.push((path, entry.size as u64));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Pull a remote directory tree to local storage using the native sync protocol. First
+/// enumerates the remote tree via `LIST`, then pulls each file into the mirrored local path,
+/// recreating local subdirectories as needed and emitting `transfer-progress` events. Continues
+/// past per-file failures, reporting them in the returned summary. When `compress` is set,
+/// chunks are expected as LZ4 frames and decompressed as they arrive.
+#[tauri::command]
+pub fn pull_dir(
+    app_handle: AppHandle,
+    device_id: String,
+    remote_dir: String,
+    local_dir: String,
+    compress: bool,
+) -> Result<DirTransferSummary, AppError> {
+    let mut client = SyncClient::connect(&device_id)?;
+    let remote_root = remote_dir.trim_end_matches('/').to_string();
+    let remote_files = collect_remote_files(&mut client, &remote_root)?;
+    let codec = codec_for(compress);
+
+    let mut files_transferred = 0usize;
+    let mut total_bytes = 0u64;
+    let mut errors = Vec::new();
+
+    for (remote_path, size) in remote_files {
+        let rel = remote_path
+            .strip_prefix(&format!("{}/", remote_root))
+            .unwrap_or(&remote_path);
+        let local_path = std::path::Path::new(&local_dir).join(rel);
+
+        if let Some(parent) = local_path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                errors.push(DirTransferError {
+                    path: remote_path,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        }
+
+        let mut on_progress = |bytes: u64, total: u64, path: &str| {
+            let _ = app_handle.emit(
+                "transfer-progress",
+                TransferProgressPayload {
+                    path: path.to_string(),
+                    bytes_transferred: bytes,
+                    total_bytes: total,
+                },
+            );
+        };
+
+        match client.pull_file(&remote_path, &local_path, codec, Some(&mut on_progress)) {
+            Ok(_) => {
+                files_transferred += 1;
+                total_bytes += size;
+            }
+            Err(e) => errors.push(DirTransferError {
+                path: remote_path,
+                message: e.message,
+            }),
+        }
+    }
+
+    Ok(DirTransferSummary {
+        files_transferred,
+        total_bytes,
+        errors,
+        codec,
+    })
+}
+
+/// Pull a single remote file to local storage using the native sync protocol, emitting
+/// `transfer-progress` events as chunks are received.
+#[tauri::command]
+pub fn pull_file_native(
+    app_handle: AppHandle,
+    device_id: String,
+    remote_path: String,
+    local_path: String,
+) -> Result<(), AppError> {
+    let mut client = SyncClient::connect(&device_id)?;
+    let mut on_progress = |bytes: u64, total: u64, path: &str| {
+        let _ = app_handle.emit(
+            "transfer-progress",
+            TransferProgressPayload {
+                path: path.to_string(),
+                bytes_transferred: bytes,
+                total_bytes: total,
+            },
+        );
+    };
+
+    client
+        .pull_file(
+            &remote_path,
+            std::path::Path::new(&local_path),
+            SyncCompression::None,
+            Some(&mut on_progress),
+        )
+        .map(|_| ())
+}
+
 /// Create a directory on device
 #[tauri::command]
 pub fn create_remote_directory(device_id: String, remote_path: String) -> Result<(), AppError> {
@@ -180,7 +528,14 @@ pub fn create_remote_directory(device_id: String, remote_path: String) -> Result
     let adb_path = executor.get_adb_path();
 
     let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "shell", "mkdir", "-p", &remote_path])
+        .args([
+            "-s",
+            &device_id,
+            "shell",
+            "mkdir",
+            "-p",
+            &quote_shell_arg(&remote_path),
+        ])
         .output()
         .map_err(|e| {
             AppError::new(
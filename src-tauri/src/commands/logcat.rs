@@ -1,28 +1,162 @@
+// Logcat Streaming Commands - Parsed, filtered logcat batching with a bounded per-device backlog
+// Reads `logcat -v threadtime` line-by-line - over a spawned `adb` process, or directly over a
+// `shell:logcat ...` socket when the executor is talking to the ADB server over the wire
+// protocol - parses each line into a `LogRecord`, drops records that don't pass the caller's
+// filter, and batches the survivors the same way `start_logcat_stream` always has (buffer size
+// or time threshold, whichever comes first). Surviving records are also kept in a bounded
+// per-device ring buffer so a newly attached UI can request a backlog snapshot instead of
+// waiting for the live stream to refill its view.
+
+use crate::adb::protocol::AdbProtocolClient;
 use crate::adb::AdbExecutor;
 use crate::command_utils::hidden_command;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
 use std::process::{Child, Stdio};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use tauri::{AppHandle, Emitter, Runtime, State};
 
+/// Default number of parsed records kept per device for backlog replay.
+const DEFAULT_BACKLOG_CAPACITY: usize = 5000;
+
+/// A running logcat stream, stoppable regardless of which executor backend produced it: a
+/// spawned `adb logcat` process, or a live socket talking `shell:logcat ...` directly to the
+/// ADB server.
+pub enum LogcatStream {
+    Process(Child),
+    Socket(TcpStream),
+}
+
+impl LogcatStream {
+    fn stop(self) {
+        match self {
+            LogcatStream::Process(mut child) => {
+                let _ = child.kill();
+            }
+            LogcatStream::Socket(stream) => {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+        }
+    }
+}
+
 pub struct LogcatState {
-    pub streams: Arc<Mutex<HashMap<String, Child>>>,
+    pub streams: Arc<Mutex<HashMap<String, LogcatStream>>>,
+    pub backlog: Arc<Mutex<HashMap<String, VecDeque<LogRecord>>>>,
 }
 
 impl LogcatState {
     pub fn new() -> Self {
         Self {
             streams: Arc::new(Mutex::new(HashMap::new())),
+            backlog: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+impl Default for LogcatState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single parsed `-v threadtime` logcat line.
+#[derive(Clone, serde::Serialize)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub level: char,
+    pub tag: String,
+    pub message: String,
+}
+
+/// Minimum-priority + tag/message filter applied server-side before a record is ever emitted
+/// or stored in the backlog. All fields are optional; an absent field doesn't filter anything.
+#[derive(Clone, Default, serde::Deserialize)]
+pub struct LogFilterSpec {
+    pub min_priority: Option<char>,
+    pub tag_allow: Option<Vec<String>>,
+    pub tag_deny: Option<Vec<String>>,
+    pub message_regex: Option<String>,
+}
+
+impl LogFilterSpec {
+    fn matches(&self, record: &LogRecord, message_regex: &Option<regex::Regex>) -> bool {
+        if let Some(min) = self.min_priority {
+            if priority_rank(record.level) < priority_rank(min) {
+                return false;
+            }
+        }
+        if let Some(allow) = &self.tag_allow {
+            if !allow.iter().any(|t| t == &record.tag) {
+                return false;
+            }
+        }
+        if let Some(deny) = &self.tag_deny {
+            if deny.iter().any(|t| t == &record.tag) {
+                return false;
+            }
+        }
+        if let Some(re) = message_regex {
+            if !re.is_match(&record.message) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Rank logcat priority letters for `min_priority` comparisons (V < D < I < W < E < F).
+fn priority_rank(level: char) -> u8 {
+    match level.to_ascii_uppercase() {
+        'V' => 0,
+        'D' => 1,
+        'I' => 2,
+        'W' => 3,
+        'E' => 4,
+        'F' => 5,
+        _ => 0,
+    }
+}
+
+lazy_static::lazy_static! {
+    /// `MM-DD HH:MM:SS.mmm  PID  TID LEVEL TAG: message`, the line shape `adb logcat -v
+    /// threadtime` produces.
+    static ref THREADTIME_RE: regex::Regex = regex::Regex::new(
+        r"^(\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d{3})\s+(\d+)\s+(\d+)\s+([VDIWEF])\s+([^:]+):\s?(.*)$"
+    ).unwrap();
+}
+
+/// Parse a single `adb logcat -v threadtime` line into a `LogRecord`, or `None` if it doesn't
+/// match the expected shape (e.g. a multi-line stack trace continuation).
+fn parse_threadtime_line(line: &str) -> Option<LogRecord> {
+    let caps = THREADTIME_RE.captures(line)?;
+    Some(LogRecord {
+        timestamp: caps[1].to_string(),
+        pid: caps[2].parse().ok()?,
+        tid: caps[3].parse().ok()?,
+        level: caps[4].chars().next()?,
+        tag: caps[5].trim().to_string(),
+        message: caps[6].to_string(),
+    })
+}
+
 #[derive(Clone, serde::Serialize)]
 pub struct LogBatchPayload {
     pub device_id: String,
-    pub lines: Vec<String>,
+    pub records: Vec<LogRecord>,
+}
+
+/// Build the `logcat` argument list (minus the leading `-s <device>` target), applying the raw
+/// logcat filterspec (e.g. `*:E`, `ActivityManager:I`) at spawn time so unwanted lines never
+/// leave the device, on top of the richer client-side `LogFilterSpec` matching applied per line.
+fn logcat_args(filter_spec: &[String]) -> Vec<String> {
+    let mut args = vec!["logcat".to_string(), "-v".to_string(), "threadtime".to_string()];
+    args.extend(filter_spec.iter().cloned());
+    args
 }
 
 #[tauri::command]
@@ -30,45 +164,85 @@ pub async fn start_logcat_stream<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, LogcatState>,
     device_id: String,
+    filter: Option<LogFilterSpec>,
+    filter_spec: Option<Vec<String>>,
+    backlog_capacity: Option<usize>,
 ) -> Result<(), String> {
     let mut streams = state.streams.lock().unwrap();
 
     // Stop existing stream for this device if any
-    if let Some(mut child) = streams.remove(&device_id) {
-        let _ = child.kill();
+    if let Some(existing) = streams.remove(&device_id) {
+        existing.stop();
     }
 
     let adb = AdbExecutor::new();
-    let adb_path = adb.get_adb_path();
+    let filter_spec = filter_spec.unwrap_or_default();
+    let args = logcat_args(&filter_spec);
 
-    if !adb_path.exists() && adb_path.to_str() != Some("adb") {
-        return Err(format!("ADB not found at expected path: {:?}", adb_path));
-    }
+    // Reuse whichever executor backend is active: a live `shell:logcat` socket when talking to
+    // the ADB server directly, or a spawned `adb logcat` process otherwise.
+    let (handle, reader): (LogcatStream, Box<dyn BufRead + Send>) =
+        if let Some(addr) = adb.server_addr() {
+            let mut client = AdbProtocolClient::connect(addr).map_err(|e| e.message.clone())?;
+            client
+                .transport(&device_id)
+                .map_err(|e| e.message.clone())?;
+            client
+                .send_request(&format!("shell:{}", args.join(" ")))
+                .map_err(|e| e.message.clone())?;
+
+            let stream = client.into_inner();
+            let reader_stream = stream
+                .try_clone()
+                .map_err(|e| format!("Failed to clone logcat socket: {}", e))?;
+            (
+                LogcatStream::Socket(stream),
+                Box::new(BufReader::new(reader_stream)),
+            )
+        } else {
+            let adb_path = adb.get_adb_path();
 
-    let mut child = hidden_command(adb_path)
-        .args(["-s", &device_id, "logcat", "-v", "time"])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|e| {
-            let err = format!("Failed to spawn logcat: {}", e);
-            println!("{}", err);
-            err
-        })?;
+            if !adb_path.exists() && adb_path.to_str() != Some("adb") {
+                return Err(format!("ADB not found at expected path: {:?}", adb_path));
+            }
+
+            let mut command_args = vec!["-s".to_string(), device_id.clone()];
+            command_args.extend(args);
+
+            let mut child = hidden_command(adb_path)
+                .args(&command_args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| {
+                    let err = format!("Failed to spawn logcat: {}", e);
+                    println!("{}", err);
+                    err
+                })?;
+
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or("Failed to capture logcat stdout")?;
 
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or("Failed to capture logcat stdout")?;
+            (LogcatStream::Process(child), Box::new(BufReader::new(stdout)))
+        };
 
-    streams.insert(device_id.clone(), child);
+    streams.insert(device_id.clone(), handle);
 
     let device_id_clone = device_id.clone();
     let sanitized_id = device_id.replace(|c: char| !c.is_alphanumeric(), "_");
     let app_handle = app.clone();
+    let backlog = state.backlog.clone();
+    let capacity = backlog_capacity.unwrap_or(DEFAULT_BACKLOG_CAPACITY);
+
+    let message_regex = filter
+        .as_ref()
+        .and_then(|f| f.message_regex.as_deref())
+        .and_then(|pattern| regex::Regex::new(pattern).ok());
+    let filter = filter.unwrap_or_default();
 
     thread::spawn(move || {
-        let reader = BufReader::new(stdout);
         let mut batch = Vec::new();
         let mut last_emit = std::time::Instant::now();
 
@@ -81,7 +255,27 @@ pub async fn start_logcat_stream<R: Runtime>(
                         continue;
                     }
 
-                    batch.push(text);
+                    let record = match parse_threadtime_line(trimmed) {
+                        Some(record) => record,
+                        None => continue,
+                    };
+
+                    if !filter.matches(&record, &message_regex) {
+                        continue;
+                    }
+
+                    {
+                        let mut backlog = backlog.lock().unwrap();
+                        let ring = backlog
+                            .entry(device_id_clone.clone())
+                            .or_insert_with(VecDeque::new);
+                        ring.push_back(record.clone());
+                        while ring.len() > capacity {
+                            ring.pop_front();
+                        }
+                    }
+
+                    batch.push(record);
 
                     // Emit batch if it's large enough or 100ms has passed
                     if batch.len() >= 50 || last_emit.elapsed().as_millis() >= 100 {
@@ -89,7 +283,7 @@ pub async fn start_logcat_stream<R: Runtime>(
                             &format!("logcat-line-{}", sanitized_id),
                             LogBatchPayload {
                                 device_id: device_id_clone.clone(),
-                                lines: batch.clone(),
+                                records: batch.clone(),
                             },
                         );
                         batch.clear();
@@ -106,7 +300,7 @@ pub async fn start_logcat_stream<R: Runtime>(
                 &format!("logcat-line-{}", sanitized_id),
                 LogBatchPayload {
                     device_id: device_id_clone.clone(),
-                    lines: batch,
+                    records: batch,
                 },
             );
         }
@@ -121,14 +315,48 @@ pub async fn stop_logcat_stream(
     device_id: String,
 ) -> Result<(), String> {
     let mut streams = state.streams.lock().unwrap();
-    if let Some(mut child) = streams.remove(&device_id) {
-        let _ = child.kill();
+    if let Some(existing) = streams.remove(&device_id) {
+        existing.stop();
     }
     Ok(())
 }
 
+/// Return the bounded backlog of parsed records accumulated for `device_id` since its stream
+/// was started, so a newly attached UI can render history without waiting for new lines.
 #[tauri::command]
-pub async fn export_logcat() -> Result<(), String> {
-    // This will be handled by the frontend using tauri-plugin-dialog
-    Ok(())
+pub async fn get_logcat_backlog(
+    state: State<'_, LogcatState>,
+    device_id: String,
+) -> Result<Vec<LogRecord>, String> {
+    let backlog = state.backlog.lock().unwrap();
+    Ok(backlog
+        .get(&device_id)
+        .map(|ring| ring.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+/// Dump `device_id`'s backlog to `output_path`, one `threadtime`-formatted line per record.
+#[tauri::command]
+pub async fn export_logcat(
+    state: State<'_, LogcatState>,
+    device_id: String,
+    output_path: String,
+) -> Result<(), String> {
+    let records: Vec<LogRecord> = {
+        let backlog = state.backlog.lock().unwrap();
+        backlog
+            .get(&device_id)
+            .map(|ring| ring.iter().cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let mut contents = String::new();
+    for record in &records {
+        contents.push_str(&format!(
+            "{} {:>5} {:>5} {} {}: {}\n",
+            record.timestamp, record.pid, record.tid, record.level, record.tag, record.message
+        ));
+    }
+
+    std::fs::write(&output_path, contents).map_err(|e| format!("Failed to export logcat: {}", e))
 }
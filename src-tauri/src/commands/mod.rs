@@ -1,20 +1,34 @@
 // Commands Module - Tauri command handlers
 // These functions are exposed to the frontend via Tauri IPC
 
+pub mod agent;
 pub mod apk;
+pub mod bootstrap;
 pub mod device;
 pub mod device_actions;
+pub mod fastboot;
 pub mod file_transfer;
 pub mod scrcpy;
 pub mod screen_capture;
+pub mod screen_mirror;
 pub mod shell;
+pub mod sideload;
+pub mod signing;
+pub mod wipe;
 pub mod wireless;
 
+pub use agent::*;
 pub use apk::*;
+pub use bootstrap::*;
 pub use device::*;
 pub use device_actions::*;
+pub use fastboot::*;
 pub use file_transfer::*;
 pub use scrcpy::*;
 pub use screen_capture::*;
+pub use screen_mirror::*;
 pub use shell::*;
+pub use sideload::*;
+pub use signing::*;
+pub use wipe::*;
 pub use wireless::*;
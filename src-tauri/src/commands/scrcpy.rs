@@ -2,7 +2,9 @@
 // Commands for starting/stopping scrcpy server and streaming
 
 use crate::error::AppError;
+use crate::services::recorder::{self, RecordingFormat, RecordingManifest};
 use crate::services::scrcpy::{self, ScrcpyConfig, ScrcpyStatus};
+use std::path::Path;
 use tauri::AppHandle;
 
 /// Start scrcpy server on a device
@@ -12,6 +14,8 @@ pub fn start_scrcpy_server(
     max_size: Option<u32>,
     bit_rate: Option<u32>,
     max_fps: Option<u8>,
+    audio: Option<bool>,
+    audio_codec: Option<String>,
     app_handle: AppHandle,
 ) -> Result<ScrcpyStatus, AppError> {
     let mut config = ScrcpyConfig::default();
@@ -25,6 +29,12 @@ pub fn start_scrcpy_server(
     if let Some(fps) = max_fps {
         config.max_fps = fps;
     }
+    if let Some(enabled) = audio {
+        config.audio = enabled;
+    }
+    if let Some(codec) = audio_codec {
+        config.audio_codec = codec;
+    }
 
     scrcpy::start_server(&device_id, config, &app_handle)
 }
@@ -146,6 +156,25 @@ pub fn scrcpy_text(device_id: String, text: String) -> Result<(), AppError> {
     scrcpy::send_control_event(&device_id, 1, &data)
 }
 
+/// Inject a string as a single INJECT_TEXT control message, instead of one event per keystroke.
+#[tauri::command]
+pub fn inject_text(device_id: String, text: String) -> Result<(), AppError> {
+    scrcpy::inject_text(&device_id, &text)
+}
+
+/// Push `text` to the device clipboard, optionally pasting it into the focused field.
+#[tauri::command]
+pub fn set_device_clipboard(device_id: String, text: String, paste: bool) -> Result<(), AppError> {
+    scrcpy::set_device_clipboard(&device_id, &text, paste)
+}
+
+/// Ask the device to push its clipboard back; the content arrives as a `scrcpy-clipboard-{id}`
+/// event rather than as a return value. `copy_key`: 0 = as-is, 1 = simulate copy, 2 = simulate cut.
+#[tauri::command]
+pub fn get_device_clipboard(device_id: String, copy_key: u8) -> Result<(), AppError> {
+    scrcpy::get_device_clipboard(&device_id, copy_key)
+}
+
 /// Request scrcpy sync (re-emit SPS/PPS/IDR headers)
 #[tauri::command]
 pub fn request_scrcpy_sync(
@@ -155,3 +184,45 @@ pub fn request_scrcpy_sync(
 ) -> Result<(), AppError> {
     scrcpy::sync_session(&device_id, &window_label, &app_handle)
 }
+
+/// Start teeing a running scrcpy session's video to segment files on disk, in addition to the
+/// live preview. `width`/`height` should match the frame size the frontend is decoding, since the
+/// recorder doesn't parse SPS itself.
+#[tauri::command]
+pub fn start_recording(
+    device_id: String,
+    output_dir: String,
+    base_name: String,
+    format: RecordingFormat,
+    width: u32,
+    height: u32,
+    fps: Option<u8>,
+) -> Result<(), AppError> {
+    recorder::start_recording(
+        &device_id,
+        Path::new(&output_dir),
+        &base_name,
+        format,
+        width,
+        height,
+        fps.unwrap_or(30),
+    )
+}
+
+/// Stop recording `device_id`, flushing the current segment, and return the segment manifest.
+#[tauri::command]
+pub fn stop_recording(device_id: String) -> Result<RecordingManifest, AppError> {
+    recorder::stop_recording(&device_id)
+}
+
+/// Report the frontend decoder's health so the adaptive bitrate controller can react. Call this
+/// periodically (e.g. once per second) while a session is streaming.
+#[tauri::command]
+pub fn report_client_stats(
+    device_id: String,
+    decode_latency_ms: u32,
+    dropped_frames: u32,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    scrcpy::report_client_stats(&device_id, decode_latency_ms, dropped_frames, &app_handle)
+}
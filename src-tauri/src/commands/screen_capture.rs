@@ -1,20 +1,72 @@
 // Screen Capture Commands - Screenshot and Screen Recording
 // Provides commands for capturing device screen
 
-use crate::adb::AdbExecutor;
+use crate::adb::{AdbExecutor, AdbProtocolClient, SyncClient, SyncCompression};
 use crate::command_utils::hidden_command;
 use crate::error::AppError;
+use crate::services::snapshot::{self, SnapshotOutput};
 use chrono::Local;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use tauri::State;
 
 #[derive(Debug, Serialize)]
 pub struct CaptureResult {
     pub success: bool,
     pub path: Option<String>,
     pub error: Option<String>,
+    /// Set by the continuous `stop_screen_recording` flow: either every pulled segment (one per
+    /// entry) or, when ffmpeg was available to concatenate them, the single combined file.
+    pub files: Option<Vec<String>>,
+    /// Total wall-clock duration of a continuous recording session, in seconds.
+    pub duration_secs: Option<f64>,
+}
+
+/// On-device filename prefix for a continuous recording session's sequentially numbered
+/// segments, e.g. `adbcompass_rec_000.mp4`, `adbcompass_rec_001.mp4`, ...
+const SEGMENT_PREFIX: &str = "adbcompass_rec_";
+
+/// A single device's in-progress continuous (segment-chained) recording.
+struct RecordingSession {
+    running: Arc<AtomicBool>,
+    segments: Arc<Mutex<Vec<String>>>,
+    started_at: Instant,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+/// Tauri-managed state tracking one `RecordingSession` per device, keyed by device id.
+pub struct RecordingState {
+    sessions: Mutex<HashMap<String, RecordingSession>>,
+}
+
+impl RecordingState {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Signal every in-progress recording session to stop, without waiting for their worker
+    /// threads to join. Used on app exit so a dangling session doesn't keep `adb shell` alive.
+    pub fn stop_all(&self) {
+        let sessions = self.sessions.lock().unwrap();
+        for session in sessions.values() {
+            session.running.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Default for RecordingState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Get the default media directory (Relative to the application executable)
@@ -87,48 +139,42 @@ pub fn take_screenshot(device_id: String, custom_save_path: Option<String>) -> C
                 success: false,
                 path: None,
                 error: Some(e.message),
+                files: None,
+                duration_secs: None,
             }
         }
     };
 
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
-
     // Generate filename with timestamp
     let timestamp = Local::now().format("%Y%m%d_%H%M%S");
     let filename = format!("screenshot_{}.png", timestamp);
     let save_path = screenshots_dir.join(&filename);
 
-    // Use adb exec-out to get raw PNG data
-    let output = match hidden_command(adb_path)
-        .args(["-s", &device_id, "exec-out", "screencap", "-p"])
-        .output()
+    // Talk to the adb server directly over `exec:` instead of spawning the `adb` binary, so
+    // this works even when the bundled binary is missing.
+    let png_data = match AdbProtocolClient::connect_default()
+        .and_then(|mut client| client.exec_out(&device_id, "screencap -p"))
     {
-        Ok(output) => output,
+        Ok(data) => data,
         Err(e) => {
             return CaptureResult {
                 success: false,
                 path: None,
-                error: Some(format!("Failed to execute screencap: {}", e)),
+                error: Some(format!("Failed to execute screencap: {}", e.message)),
+                files: None,
+                duration_secs: None,
             }
         }
     };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return CaptureResult {
-            success: false,
-            path: None,
-            error: Some(format!("Screencap failed: {}", stderr)),
-        };
-    }
-
     // Save the PNG data to file
-    if let Err(e) = fs::write(&save_path, &output.stdout) {
+    if let Err(e) = fs::write(&save_path, &png_data) {
         return CaptureResult {
             success: false,
             path: None,
             error: Some(format!("Failed to save screenshot: {}", e)),
+            files: None,
+            duration_secs: None,
         };
     }
 
@@ -136,46 +182,187 @@ pub fn take_screenshot(device_id: String, custom_save_path: Option<String>) -> C
         success: true,
         path: Some(save_path.to_string_lossy().to_string()),
         error: None,
+        files: None,
+        duration_secs: None,
     }
 }
 
-/// Start screen recording on device
-#[tauri::command]
-pub fn start_screen_recording(device_id: String) -> CaptureResult {
+fn remote_segment_path(index: u32) -> String {
+    format!("/sdcard/{}{:03}.mp4", SEGMENT_PREFIX, index)
+}
+
+/// Worker loop for a continuous recording session: launch `screenrecord` to the next segment
+/// file and block until it exits, whether that's Android's ~180s per-clip cap or a
+/// `pkill -SIGINT` issued by `stop_screen_recording`, then chain into the next segment unless
+/// asked to stop.
+fn record_segments_loop(device_id: String, running: Arc<AtomicBool>, segments: Arc<Mutex<Vec<String>>>) {
     let executor = AdbExecutor::new();
     let adb_path = executor.get_adb_path();
+    let mut index = 0u32;
 
-    // Start recording in background on device
-    // Recording to /sdcard/adbcompass_recording.mp4
-    let result = hidden_command(adb_path)
-        .args([
-            "-s",
-            &device_id,
-            "shell",
-            "screenrecord",
-            "/sdcard/adbcompass_recording.mp4",
-        ])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn();
+    while running.load(Ordering::Relaxed) {
+        let remote_path = remote_segment_path(index);
 
-    match result {
-        Ok(_) => CaptureResult {
-            success: true,
-            path: None,
-            error: None,
-        },
-        Err(e) => CaptureResult {
+        let child = hidden_command(adb_path)
+            .args(["-s", &device_id, "shell", "screenrecord", &remote_path])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(_) => break,
+        };
+
+        let _ = child.wait();
+
+        segments.lock().unwrap().push(remote_path);
+        index += 1;
+    }
+}
+
+/// Start a continuous screen recording on device. Unlike a single `screenrecord` invocation,
+/// this transparently chains sequentially numbered segments so the effective recording isn't
+/// capped at Android's ~180 seconds per clip; `stop_screen_recording` finalizes and collects
+/// whatever segments were produced.
+#[tauri::command]
+pub fn start_screen_recording(
+    device_id: String,
+    state: State<'_, RecordingState>,
+) -> CaptureResult {
+    let mut sessions = state.sessions.lock().unwrap();
+    if sessions.contains_key(&device_id) {
+        return CaptureResult {
             success: false,
             path: None,
-            error: Some(format!("Failed to start recording: {}", e)),
+            error: Some("A recording is already in progress for this device".to_string()),
+            files: None,
+            duration_secs: None,
+        };
+    }
+
+    let running = Arc::new(AtomicBool::new(true));
+    let segments = Arc::new(Mutex::new(Vec::new()));
+
+    let running_clone = running.clone();
+    let segments_clone = segments.clone();
+    let device_id_clone = device_id.clone();
+    let worker = thread::spawn(move || {
+        record_segments_loop(device_id_clone, running_clone, segments_clone);
+    });
+
+    sessions.insert(
+        device_id,
+        RecordingSession {
+            running,
+            segments,
+            started_at: Instant::now(),
+            worker: Some(worker),
         },
+    );
+
+    CaptureResult {
+        success: true,
+        path: None,
+        error: None,
+        files: None,
+        duration_secs: None,
     }
 }
 
-/// Stop screen recording and pull file to local
+/// True if `ffmpeg` is on `PATH` and runs successfully, used to decide whether a continuous
+/// recording's segments get concatenated into one file or left as an indexed set.
+fn ffmpeg_available() -> bool {
+    std::process::Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Concatenate `segments` (in order) into `output` via ffmpeg's concat demuxer, stream-copying
+/// rather than re-encoding since every segment already shares the same codec/resolution.
+fn concat_segments(segments: &[PathBuf], output: &Path) -> Result<(), String> {
+    let list_path = output.with_extension("concat.txt");
+    let mut list_contents = String::new();
+    for segment in segments {
+        list_contents.push_str(&format!(
+            "file '{}'\n",
+            segment.to_string_lossy().replace('\'', "'\\''")
+        ));
+    }
+    fs::write(&list_path, list_contents).map_err(|e| e.to_string())?;
+
+    let status = std::process::Command::new("ffmpeg")
+        .args(["-y", "-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_path)
+        .args(["-c", "copy"])
+        .arg(output)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    let _ = fs::remove_file(&list_path);
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("ffmpeg exited with {}", status)),
+        Err(e) => Err(format!("Failed to run ffmpeg: {}", e)),
+    }
+}
+
+/// Stop a continuous screen recording, pull every produced segment, and either keep them as an
+/// indexed set under a per-session subfolder or concatenate them into a single file when
+/// ffmpeg is available, cleaning up the on-device segment files either way.
 #[tauri::command]
-pub fn stop_screen_recording(device_id: String, custom_save_path: Option<String>) -> CaptureResult {
+pub fn stop_screen_recording(
+    device_id: String,
+    custom_save_path: Option<String>,
+    state: State<'_, RecordingState>,
+) -> CaptureResult {
+    let session = state.sessions.lock().unwrap().remove(&device_id);
+
+    let mut session = match session {
+        Some(session) => session,
+        None => {
+            return CaptureResult {
+                success: false,
+                path: None,
+                error: Some("No recording in progress for this device".to_string()),
+                files: None,
+                duration_secs: None,
+            }
+        }
+    };
+
+    session.running.store(false, Ordering::SeqCst);
+
+    // Ask the in-flight segment to finalize; the worker thread's blocking `child.wait()` then
+    // observes the `adb shell` connection close and appends the now-complete segment before
+    // exiting its loop.
+    let _ = AdbProtocolClient::connect_default().and_then(|mut client| {
+        client.run_device_service(&device_id, "shell:pkill -SIGINT screenrecord")
+    });
+
+    if let Some(worker) = session.worker.take() {
+        let _ = worker.join();
+    }
+
+    let duration_secs = session.started_at.elapsed().as_secs_f64();
+    let remote_segments = session.segments.lock().unwrap().clone();
+
+    if remote_segments.is_empty() {
+        return CaptureResult {
+            success: false,
+            path: None,
+            error: Some("No segments were recorded".to_string()),
+            files: None,
+            duration_secs: Some(duration_secs),
+        };
+    }
+
     let (_, recordings_dir) = match ensure_capture_dirs(custom_save_path) {
         Ok(dirs) => dirs,
         Err(e) => {
@@ -183,104 +370,124 @@ pub fn stop_screen_recording(device_id: String, custom_save_path: Option<String>
                 success: false,
                 path: None,
                 error: Some(e.message),
+                files: None,
+                duration_secs: Some(duration_secs),
             }
         }
     };
 
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
-
-    // Kill screenrecord process on device
-    let _ = hidden_command(&adb_path)
-        .args([
-            "-s",
-            &device_id,
-            "shell",
-            "pkill",
-            "-SIGINT",
-            "screenrecord",
-        ])
-        .output();
-
-    // Wait a bit for file to be finalized
-    std::thread::sleep(std::time::Duration::from_millis(1000));
-
-    // Generate local filename
     let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("recording_{}.mp4", timestamp);
-    let save_path = recordings_dir.join(&filename);
-
-    // Pull recording from device
-    let output = hidden_command(&adb_path)
-        .args([
-            "-s",
-            &device_id,
-            "pull",
-            "/sdcard/adbcompass_recording.mp4",
-            &save_path.to_string_lossy(),
-        ])
-        .output();
-
-    match output {
-        Ok(o) if o.status.success() => {
-            // Clean up file on device
-            let _ = hidden_command(&adb_path)
-                .args([
-                    "-s",
-                    &device_id,
-                    "shell",
-                    "rm",
-                    "/sdcard/adbcompass_recording.mp4",
-                ])
-                .output();
-
-            CaptureResult {
-                success: true,
-                path: Some(save_path.to_string_lossy().to_string()),
-                error: None,
-            }
-        }
-        Ok(o) => {
-            let stderr = String::from_utf8_lossy(&o.stderr);
-            CaptureResult {
+    let session_dir = recordings_dir.join(format!("recording_{}", timestamp));
+    if let Err(e) = fs::create_dir_all(&session_dir) {
+        return CaptureResult {
+            success: false,
+            path: None,
+            error: Some(format!("Failed to create session directory: {}", e)),
+            files: None,
+            duration_secs: Some(duration_secs),
+        };
+    }
+
+    let mut local_paths = Vec::with_capacity(remote_segments.len());
+    for (i, remote_path) in remote_segments.iter().enumerate() {
+        let local_path = session_dir.join(format!("segment_{:03}.mp4", i));
+        let pull = SyncClient::connect(&device_id).and_then(|mut client| {
+            client.pull_file(remote_path, &local_path, SyncCompression::None, None)
+        });
+        if let Err(e) = pull {
+            return CaptureResult {
                 success: false,
                 path: None,
-                error: Some(format!("Failed to pull recording: {}", stderr)),
+                error: Some(format!("Failed to pull segment {}: {}", remote_path, e.message)),
+                files: None,
+                duration_secs: Some(duration_secs),
+            };
+        }
+        local_paths.push(local_path);
+    }
+
+    // Clean up segment files on device now that they're all pulled
+    for remote_path in &remote_segments {
+        let _ = AdbProtocolClient::connect_default()
+            .and_then(|mut client| client.run_device_service(&device_id, &format!("shell:rm {}", remote_path)));
+    }
+
+    if local_paths.len() > 1 && ffmpeg_available() {
+        let combined = session_dir.join("recording.mp4");
+        if concat_segments(&local_paths, &combined).is_ok() {
+            for segment in &local_paths {
+                let _ = fs::remove_file(segment);
             }
+            return CaptureResult {
+                success: true,
+                path: Some(combined.to_string_lossy().to_string()),
+                error: None,
+                files: Some(vec![combined.to_string_lossy().to_string()]),
+                duration_secs: Some(duration_secs),
+            };
         }
-        Err(e) => CaptureResult {
-            success: false,
-            path: None,
-            error: Some(format!("Failed to pull recording: {}", e)),
-        },
+    }
+
+    let files: Vec<String> = local_paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    CaptureResult {
+        success: true,
+        path: Some(session_dir.to_string_lossy().to_string()),
+        error: None,
+        files: Some(files),
+        duration_secs: Some(duration_secs),
     }
 }
 
 /// Get a single frame of the device screen for preview
 #[tauri::command]
 pub fn get_screen_frame(device_id: String) -> Result<Vec<u8>, AppError> {
-    let executor = AdbExecutor::new();
-    let adb_path = executor.get_adb_path();
+    let mut client = AdbProtocolClient::connect_default()
+        .map_err(|e| AppError::new("SCREEN_FRAME_FAILED", &format!("Failed to get screen: {}", e.message)))?;
 
-    let output = hidden_command(adb_path)
-        .args(["-s", &device_id, "exec-out", "screencap", "-p"])
-        .output()
-        .map_err(|e| {
-            AppError::new(
-                "SCREEN_FRAME_FAILED",
-                &format!("Failed to get screen: {}", e),
-            )
-        })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::new(
-            "SCREEN_FRAME_FAILED",
-            &format!("Screencap failed: {}", stderr),
-        ));
-    }
+    client
+        .exec_out(&device_id, "screencap -p")
+        .map_err(|e| AppError::new("SCREEN_FRAME_FAILED", &format!("Screencap failed: {}", e.message)))
+}
 
-    Ok(output.stdout)
+/// Result of a `capture_snapshot` call: exactly one of `data`/`path` is set, depending on
+/// whether an `output_path` was requested.
+#[derive(Debug, Serialize)]
+pub struct SnapshotResult {
+    pub data: Option<String>,
+    pub path: Option<String>,
+}
+
+/// Decode a single still JPEG from the device's live scrcpy stream, without the frontend having
+/// to screenshot a canvas. Reuses the session's cached SPS/PPS/IDR, so a scrcpy session must
+/// already be running for `device_id`.
+#[tauri::command]
+pub fn capture_snapshot(
+    device_id: String,
+    quality: Option<u8>,
+    scale: Option<f32>,
+    output_path: Option<String>,
+) -> Result<SnapshotResult, AppError> {
+    let result = snapshot::capture_snapshot(
+        &device_id,
+        quality.unwrap_or(85),
+        scale.unwrap_or(1.0),
+        output_path.map(PathBuf::from),
+    )?;
+
+    Ok(match result {
+        SnapshotOutput::Base64(data) => SnapshotResult {
+            data: Some(data),
+            path: None,
+        },
+        SnapshotOutput::Path(path) => SnapshotResult {
+            data: None,
+            path: Some(path),
+        },
+    })
 }
 
 /// Open the captures folder in the system file explorer
@@ -358,6 +565,8 @@ pub fn save_capture_file(
                 success: false,
                 path: None,
                 error: Some(format!("Failed to create directory: {}", e)),
+                files: None,
+                duration_secs: None,
             };
         }
         target_dir.join(&filename)
@@ -372,6 +581,8 @@ pub fn save_capture_file(
                 success: false,
                 path: None,
                 error: Some(format!("Failed to decode base64: {}", e)),
+                files: None,
+                duration_secs: None,
             };
         }
     };
@@ -382,6 +593,8 @@ pub fn save_capture_file(
             success: false,
             path: None,
             error: Some(format!("Failed to save file: {}", e)),
+            files: None,
+            duration_secs: None,
         };
     }
 
@@ -389,5 +602,7 @@ pub fn save_capture_file(
         success: true,
         path: Some(save_path.to_string_lossy().to_string()),
         error: None,
+        files: None,
+        duration_secs: None,
     }
 }
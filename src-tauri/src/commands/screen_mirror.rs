@@ -0,0 +1,159 @@
+// Screen Mirror Commands - Live H.264 preview via `adb exec-out screenrecord`
+// Cheaper than hammering `get_screen_frame` in a loop: one long-lived child process streams an
+// encoded byte stream instead of one PNG per call. Batching mirrors `start_logcat_stream`'s
+// buffer/time-threshold flush, just on raw bytes instead of lines.
+
+use crate::adb::AdbExecutor;
+use crate::command_utils::hidden_command;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+/// Flush a batch once it reaches this many bytes...
+const FLUSH_THRESHOLD_BYTES: usize = 64 * 1024;
+/// ...or once this much time has passed since the last flush, whichever comes first.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct ScreenMirrorState {
+    pub streams: Arc<Mutex<HashMap<String, Child>>>,
+}
+
+impl ScreenMirrorState {
+    pub fn new() -> Self {
+        Self {
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for ScreenMirrorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct ScreenMirrorChunkPayload {
+    pub device_id: String,
+    pub data: String,
+}
+
+/// Start mirroring `device_id`'s screen as a live H.264 byte stream, replacing any mirror
+/// already running for that device.
+#[tauri::command]
+pub async fn start_screen_mirror<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ScreenMirrorState>,
+    device_id: String,
+    bitrate: Option<u32>,
+    max_size: Option<u32>,
+) -> Result<(), String> {
+    let mut streams = state.streams.lock().unwrap();
+
+    // Stop existing mirror for this device if any
+    if let Some(mut child) = streams.remove(&device_id) {
+        let _ = child.kill();
+    }
+
+    let adb = AdbExecutor::new();
+    let adb_path = adb.get_adb_path();
+
+    if !adb_path.exists() && adb_path.to_str() != Some("adb") {
+        return Err(format!("ADB not found at expected path: {:?}", adb_path));
+    }
+
+    let mut args = vec![
+        "-s".to_string(),
+        device_id.clone(),
+        "exec-out".to_string(),
+        "screenrecord".to_string(),
+        "--output-format=h264".to_string(),
+    ];
+    if let Some(bitrate) = bitrate {
+        args.push(format!("--bit-rate={}", bitrate));
+    }
+    if let Some(max_size) = max_size {
+        args.push(format!("--size={0}x{0}", max_size));
+    }
+    args.push("-".to_string());
+
+    let mut child = hidden_command(adb_path)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            let err = format!("Failed to spawn screenrecord: {}", e);
+            println!("{}", err);
+            err
+        })?;
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or("Failed to capture screenrecord stdout")?;
+
+    streams.insert(device_id.clone(), child);
+
+    let device_id_clone = device_id.clone();
+    let sanitized_id = device_id.replace(|c: char| !c.is_alphanumeric(), "_");
+    let app_handle = app.clone();
+
+    thread::spawn(move || {
+        let mut read_buf = [0u8; 8192];
+        let mut batch: Vec<u8> = Vec::with_capacity(FLUSH_THRESHOLD_BYTES);
+        let mut last_emit = Instant::now();
+
+        loop {
+            match stdout.read(&mut read_buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    batch.extend_from_slice(&read_buf[..n]);
+
+                    if batch.len() >= FLUSH_THRESHOLD_BYTES || last_emit.elapsed() >= FLUSH_INTERVAL
+                    {
+                        emit_chunk(&app_handle, &sanitized_id, &device_id_clone, &batch);
+                        batch.clear();
+                        last_emit = Instant::now();
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        // Final flush
+        if !batch.is_empty() {
+            emit_chunk(&app_handle, &sanitized_id, &device_id_clone, &batch);
+        }
+    });
+
+    Ok(())
+}
+
+fn emit_chunk<R: Runtime>(app: &AppHandle<R>, sanitized_id: &str, device_id: &str, data: &[u8]) {
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+    let _ = app.emit(
+        &format!("screen-mirror-chunk-{}", sanitized_id),
+        ScreenMirrorChunkPayload {
+            device_id: device_id.to_string(),
+            data: encoded,
+        },
+    );
+}
+
+/// Stop mirroring `device_id`'s screen, killing the underlying `screenrecord` process.
+#[tauri::command]
+pub async fn stop_screen_mirror(
+    state: State<'_, ScreenMirrorState>,
+    device_id: String,
+) -> Result<(), String> {
+    let mut streams = state.streams.lock().unwrap();
+    if let Some(mut child) = streams.remove(&device_id) {
+        let _ = child.kill();
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// Sideload Flow - Drives a full OTA/recovery-ZIP flash end to end: reboot into recovery, wait
+// for the device to re-enumerate in sideload mode, then stream the package via `adb sideload`
+// while translating recovery's progress and error text into Tauri events.
+
+use crate::adb::recovery_flash::{self, SIDELOAD_WAIT_TIMEOUT};
+use crate::adb::AdbExecutor;
+use crate::command_utils::hidden_command;
+use crate::commands::device_actions::reboot_device;
+use crate::error::AppError;
+use serde::Serialize;
+use std::io::Read;
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
+
+/// Progress payload emitted while `flash_recovery_package` works through its stages.
+#[derive(Clone, Serialize)]
+pub struct RecoveryFlashProgressPayload {
+    pub device_id: String,
+    pub stage: String,
+    pub percent: Option<u8>,
+}
+
+fn emit_stage(app_handle: &AppHandle, device_id: &str, stage: &str, percent: Option<u8>) {
+    let _ = app_handle.emit(
+        "recovery-flash-progress",
+        RecoveryFlashProgressPayload {
+            device_id: device_id.to_string(),
+            stage: stage.to_string(),
+            percent,
+        },
+    );
+}
+
+/// Reboot a device into recovery, wait for it to re-enumerate in sideload mode, then flash
+/// `zip_path` via `adb sideload`, emitting `recovery-flash-progress` events throughout. Handles
+/// both recovery sideload back-ends (classic block serving and FUSE-based) and surfaces a clear
+/// error when recovery rejects the package's signature.
+#[tauri::command]
+pub fn flash_recovery_package(
+    app_handle: AppHandle,
+    device_id: String,
+    zip_path: String,
+) -> Result<String, AppError> {
+    emit_stage(&app_handle, &device_id, "rebooting", None);
+    reboot_device(device_id.clone(), Some("recovery".to_string()))?;
+
+    emit_stage(&app_handle, &device_id, "waiting-for-sideload", None);
+    recovery_flash::wait_for_sideload_mode(&device_id, SIDELOAD_WAIT_TIMEOUT)?;
+
+    emit_stage(&app_handle, &device_id, "sideloading", Some(0));
+    run_adb_sideload(&app_handle, &device_id, &zip_path)
+}
+
+/// Run `adb sideload <zip_path>` as a subprocess and translate its output into progress events,
+/// reading byte-by-byte since recovery reports progress via carriage-return updates rather than
+/// newlines.
+fn run_adb_sideload(
+    app_handle: &AppHandle,
+    device_id: &str,
+    zip_path: &str,
+) -> Result<String, AppError> {
+    let executor = AdbExecutor::new();
+    let adb_path = executor.get_adb_path();
+
+    let mut child = hidden_command(adb_path)
+        .args(["-s", device_id, "sideload", zip_path])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            AppError::new(
+                "SIDELOAD_SPAWN_FAILED",
+                &format!("Failed to start adb sideload: {}", e),
+            )
+        })?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::new("SIDELOAD_SPAWN_FAILED", "Failed to capture adb sideload output"))?;
+
+    let mut last_percent = None;
+    let mut signature_failure = false;
+
+    for_each_progress_line(stdout, |line| {
+        if recovery_flash::is_signature_failure(line) {
+            signature_failure = true;
+        } else if let Some(percent) = recovery_flash::parse_progress_percent(line) {
+            if Some(percent) != last_percent {
+                last_percent = Some(percent);
+                emit_stage(app_handle, device_id, "sideloading", Some(percent));
+            }
+        }
+    });
+
+    let status = child.wait().map_err(|e| {
+        AppError::new(
+            "SIDELOAD_FAILED",
+            &format!("Failed to wait on adb sideload: {}", e),
+        )
+    })?;
+
+    if signature_failure {
+        return Err(AppError::new(
+            "SIDELOAD_SIGNATURE_INVALID",
+            "Recovery rejected the package: signature verification failed. Confirm the ZIP is signed for this device/ROM.",
+        ));
+    }
+
+    if status.success() {
+        emit_stage(app_handle, device_id, "complete", Some(100));
+        Ok("Package sideloaded successfully".to_string())
+    } else {
+        Err(AppError::new(
+            "SIDELOAD_FAILED",
+            "adb sideload exited with an error. Check that the device is still in sideload mode.",
+        ))
+    }
+}
+
+/// Stream bytes and invoke `on_line` as each one completes, treating both `\n` and `\r` as line
+/// breaks so that recovery's in-place progress updates (printed with `\r`, not `\n`) are seen as
+/// they arrive instead of only once the process exits.
+fn for_each_progress_line<R: Read>(mut reader: R, mut on_line: impl FnMut(&str)) {
+    let mut current = String::new();
+    let mut byte = [0u8; 1];
+
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) => match byte[0] {
+                b'\n' | b'\r' => {
+                    if !current.is_empty() {
+                        on_line(&current);
+                        current.clear();
+                    }
+                }
+                b => current.push(b as char),
+            },
+            Err(_) => break,
+        }
+    }
+
+    if !current.is_empty() {
+        on_line(&current);
+    }
+}
@@ -0,0 +1,40 @@
+// Signing Commands - Tauri commands for the APK zipalign/apksigner pipeline
+// Lets users sign locally-built APKs without juggling command-line tools.
+
+use crate::adb::AdbExecutor;
+use crate::apk::{AndroidStorageInput, InstallResult};
+use crate::error::AppError;
+use crate::signing::{ApkSigner, KeyConfig, SignResult, SigningInfo};
+
+/// Page-align and sign an APK, using the supplied key config or an ephemeral debug keystore.
+#[tauri::command]
+pub fn sign_apk(apk_path: String, key_config: Option<KeyConfig>) -> Result<SignResult, AppError> {
+    ApkSigner::new().sign(&apk_path, key_config)
+}
+
+/// Check whether an APK is signed, and if so with which scheme(s) and certificate fingerprint.
+#[tauri::command]
+pub fn is_apk_signed(apk_path: String) -> Result<SigningInfo, AppError> {
+    ApkSigner::new().verify(&apk_path)
+}
+
+/// Zipalign and sign an unsigned developer build with the managed debug keystore, then install
+/// the result, so an unsigned APK can be sideloaded without external tooling.
+#[tauri::command]
+pub fn sign_and_install(
+    device_id: String,
+    apk_path: String,
+    storage: Option<AndroidStorageInput>,
+) -> InstallResult {
+    let signed = match ApkSigner::new().sign(&apk_path, None) {
+        Ok(result) => result,
+        Err(e) => return InstallResult::failure(&device_id, &e.message, Some(&e.code)),
+    };
+
+    if !signed.success {
+        return InstallResult::failure(&device_id, &signed.message, None);
+    }
+
+    let executor = AdbExecutor::new();
+    executor.install_apk_with_storage(&device_id, &signed.apk_path, storage.unwrap_or_default())
+}
@@ -0,0 +1,37 @@
+// Wipe Commands - Tauri command for triggering a factory reset / targeted wipe through
+// recovery, instead of only rebooting and leaving the user to drive recovery's menus by hand.
+
+use crate::adb::wipe::{self, WipeOptions, WipeOutcome};
+use crate::error::AppError;
+
+/// Callers must pass this exact token in `confirm` or `factory_reset` refuses to run. This is
+/// a destructive, hard-to-reverse operation, so it shouldn't be reachable by a single
+/// accidental click.
+const FACTORY_RESET_CONFIRM_TOKEN: &str = "CONFIRM_FACTORY_RESET";
+
+/// Trigger a factory reset / targeted data-cache wipe via recovery. `confirm` must equal
+/// `"CONFIRM_FACTORY_RESET"` or the command is rejected before touching the device. Reports
+/// back how the wipe was actually carried out: executed directly, staged with a reboot into
+/// recovery, or rejected (e.g. a locked/verified-boot device refusing to touch `/cache`).
+#[tauri::command]
+pub fn factory_reset(
+    device_id: String,
+    wipe_data: bool,
+    wipe_cache: bool,
+    confirm: String,
+) -> Result<WipeOutcome, AppError> {
+    if confirm != FACTORY_RESET_CONFIRM_TOKEN {
+        return Err(AppError::new(
+            "FACTORY_RESET_NOT_CONFIRMED",
+            "Factory reset requires the exact confirmation token; refusing to proceed.",
+        ));
+    }
+
+    wipe::factory_reset(
+        &device_id,
+        &WipeOptions {
+            wipe_data,
+            wipe_cache,
+        },
+    )
+}
@@ -48,6 +48,37 @@ pub async fn connect_wireless(ip: String, port: String) -> Result<String, String
     }
 }
 
+/// Pair with a device advertising wireless debugging (Android 11+), using the six-digit code
+/// shown in Developer Options > Wireless debugging > Pair device with pairing code. This is a
+/// one-time handshake; once paired, `connect_wireless` on the device's regular wireless
+/// debugging port is enough for subsequent connections.
+#[tauri::command]
+pub async fn adb_pair(ip: String, port: String, code: String) -> Result<String, String> {
+    let adb = AdbExecutor::new();
+    let address = format!("{}:{}", ip, port);
+
+    let output = hidden_command(adb.get_adb_path())
+        .args(["pair", &address, &code])
+        .output()
+        .map_err(|e| format!("Pairing failed: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result = stdout.trim().to_string();
+
+    if result.contains("Successfully paired") {
+        Ok(result)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(if !result.is_empty() {
+            result
+        } else if !stderr.trim().is_empty() {
+            stderr.trim().to_string()
+        } else {
+            "Pairing failed - no response".to_string()
+        })
+    }
+}
+
 /// Disconnect a wirelessly connected device
 #[tauri::command]
 pub async fn disconnect_wireless(ip: String, port: String) -> Result<String, String> {
@@ -46,6 +46,7 @@ pub enum AdbError {
     DeviceNotFound(String),
     Unauthorized(String),
     Timeout,
+    BootstrapFailed(String),
 }
 
 impl From<AdbError> for AppError {
@@ -79,6 +80,51 @@ impl From<AdbError> for AppError {
                 "ADB_TIMEOUT",
                 "ADB command timed out",
             ),
+            AdbError::BootstrapFailed(msg) => AppError::with_details(
+                "ADB_BOOTSTRAP_FAILED",
+                "Failed to download or install platform-tools",
+                &msg,
+            ),
+        }
+    }
+}
+
+/// Fastboot-specific errors
+#[derive(Debug, Clone, Serialize)]
+pub enum FastbootError {
+    NotFound,
+    ExecutionFailed(String),
+    ParseError(String),
+    DeviceNotFound(String),
+    Timeout,
+}
+
+impl From<FastbootError> for AppError {
+    fn from(err: FastbootError) -> Self {
+        match err {
+            FastbootError::NotFound => AppError::new(
+                "FASTBOOT_NOT_FOUND",
+                "fastboot executable not found. Please ensure Android platform-tools are installed.",
+            ),
+            FastbootError::ExecutionFailed(msg) => AppError::with_details(
+                "FASTBOOT_EXECUTION_FAILED",
+                "Failed to execute fastboot command",
+                &msg,
+            ),
+            FastbootError::ParseError(msg) => AppError::with_details(
+                "FASTBOOT_PARSE_ERROR",
+                "Failed to parse fastboot output",
+                &msg,
+            ),
+            FastbootError::DeviceNotFound(id) => AppError::with_details(
+                "FASTBOOT_DEVICE_NOT_FOUND",
+                "Device not found in the bootloader",
+                &id,
+            ),
+            FastbootError::Timeout => AppError::new(
+                "FASTBOOT_TIMEOUT",
+                "fastboot command timed out",
+            ),
         }
     }
 }
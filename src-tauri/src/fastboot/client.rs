@@ -0,0 +1,138 @@
+// Fastboot Client - Low-level fastboot process execution
+// Mirrors `adb::client::AdbClient`: finding the fastboot path and running commands with a
+// timeout, since a device wedged in the bootloader can leave `fastboot` hanging indefinitely.
+
+use crate::command_utils::hidden_command;
+use crate::error::{AppError, FastbootError};
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+/// Default timeout for fastboot commands. Flashing large partitions can take much longer, so
+/// callers doing a flash should pass their own, more generous, timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A low-level client for executing fastboot commands.
+pub struct FastbootClient {
+    fastboot_path: PathBuf,
+}
+
+impl Default for FastbootClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FastbootClient {
+    /// Initialize a new fastboot client, automatically discovering the fastboot path.
+    pub fn new() -> Self {
+        Self {
+            fastboot_path: Self::discover_fastboot(),
+        }
+    }
+
+    /// Initialize a fastboot client with a specific path.
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            fastboot_path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the path to the fastboot executable being used.
+    pub fn fastboot_path(&self) -> &PathBuf {
+        &self.fastboot_path
+    }
+
+    /// Run a fastboot command with the default timeout.
+    pub fn execute(&self, args: &[&str]) -> Result<Output, AppError> {
+        self.execute_with_timeout(args, DEFAULT_TIMEOUT)
+    }
+
+    /// Run a fastboot command with a caller-provided timeout.
+    pub fn execute_with_timeout(&self, args: &[&str], timeout: Duration) -> Result<Output, AppError> {
+        let mut cmd = hidden_command(&self.fastboot_path);
+        cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(|e| {
+            AppError::from(FastbootError::ExecutionFailed(format!(
+                "Failed to spawn fastboot: {}",
+                e
+            )))
+        })?;
+
+        match child.wait_timeout(timeout).map_err(|e| {
+            AppError::from(FastbootError::ExecutionFailed(format!("Wait error: {}", e)))
+        })? {
+            Some(_) => child.wait_with_output().map_err(|e| {
+                AppError::from(FastbootError::ExecutionFailed(format!(
+                    "Output error: {}",
+                    e
+                )))
+            }),
+            None => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(AppError::from(FastbootError::Timeout))
+            }
+        }
+    }
+
+    /// Discover the fastboot path by checking bundled locations alongside adb and the system
+    /// path.
+    fn discover_fastboot() -> PathBuf {
+        Self::find_bundled_fastboot().unwrap_or_else(|| PathBuf::from("fastboot"))
+    }
+
+    /// Check for a bundled fastboot binary next to the bundled adb one.
+    fn find_bundled_fastboot() -> Option<PathBuf> {
+        let exe_path = std::env::current_exe().ok()?;
+        let exe_dir = exe_path.parent()?;
+        let exe_name = if cfg!(target_os = "windows") {
+            "fastboot.exe"
+        } else {
+            "fastboot"
+        };
+
+        let possible_paths = [
+            // Development paths
+            exe_dir
+                .parent()
+                .and_then(|p| p.parent())
+                .map(|p| p.join("binaries").join(exe_name)),
+            exe_dir
+                .parent()
+                .and_then(|p| p.parent())
+                .and_then(|p| p.parent())
+                .map(|p| p.join("src-tauri").join("binaries").join(exe_name)),
+            // Production paths
+            Some(exe_dir.join("resources").join("binaries").join(exe_name)),
+            Some(exe_dir.join("binaries").join(exe_name)),
+            Some(exe_dir.join(exe_name)),
+            Some(exe_dir.join("resources").join(exe_name)),
+            // CWD Fallbacks
+            Some(PathBuf::from("binaries").join(exe_name)),
+            Some(PathBuf::from("src-tauri").join("binaries").join(exe_name)),
+        ];
+
+        possible_paths.into_iter().flatten().find(|p| p.exists())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fastboot_client_initialization() {
+        let client = FastbootClient::new();
+        assert!(!client.fastboot_path().as_os_str().is_empty());
+    }
+
+    #[test]
+    fn test_fastboot_client_with_custom_path() {
+        let path = PathBuf::from("/usr/local/bin/fastboot_test");
+        let client = FastbootClient::with_path(&path);
+        assert_eq!(client.fastboot_path(), &path);
+    }
+}
@@ -0,0 +1,108 @@
+// Fastboot Command Builder - Typed builder for fastboot commands
+// Mirrors `adb::command_builder`: a fluent API to construct fastboot commands with type safety.
+
+/// Represents a variety of fastboot commands
+#[derive(Debug, Clone)]
+pub enum FastbootCommand {
+    Devices { long: bool },
+    GetVar(String),
+    Flash { partition: String, image: String },
+    Erase(String),
+    Boot(String),
+    SetActive(String),
+    Reboot { mode: Option<String> },
+}
+
+impl FastbootCommand {
+    /// Convert the command into a vector of arguments for the fastboot process
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            FastbootCommand::Devices { long } => {
+                let mut args = vec!["devices".into()];
+                if *long {
+                    args.push("-l".into());
+                }
+                args
+            }
+            FastbootCommand::GetVar(name) => vec!["getvar".into(), name.clone()],
+            FastbootCommand::Flash { partition, image } => {
+                vec!["flash".into(), partition.clone(), image.clone()]
+            }
+            FastbootCommand::Erase(partition) => vec!["erase".into(), partition.clone()],
+            FastbootCommand::Boot(image) => vec!["boot".into(), image.clone()],
+            FastbootCommand::SetActive(slot) => vec!["set_active".into(), slot.clone()],
+            FastbootCommand::Reboot { mode } => {
+                let mut args = vec!["reboot".into()];
+                if let Some(m) = mode {
+                    args.push(m.clone());
+                }
+                args
+            }
+        }
+    }
+}
+
+/// Builder for constructing fastboot commands targeting specific devices
+pub struct FastbootCommandBuilder {
+    serial: Option<String>,
+}
+
+impl FastbootCommandBuilder {
+    /// Create a new builder
+    pub fn new() -> Self {
+        Self { serial: None }
+    }
+
+    /// Target a specific device by its serial number
+    pub fn target(mut self, serial: &str) -> Self {
+        self.serial = Some(serial.to_string());
+        self
+    }
+
+    /// Construct a full argument list including device targeting strings
+    pub fn build(&self, command: FastbootCommand) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref serial) = self.serial {
+            args.push("-s".into());
+            args.push(serial.clone());
+        }
+
+        args.extend(command.to_args());
+        args
+    }
+}
+
+impl Default for FastbootCommandBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_to_args() {
+        let cmd = FastbootCommand::Flash {
+            partition: "boot".into(),
+            image: "boot.img".into(),
+        };
+        assert_eq!(cmd.to_args(), vec!["flash", "boot", "boot.img"]);
+    }
+
+    #[test]
+    fn test_builder_with_device() {
+        let builder = FastbootCommandBuilder::new().target("R58M12345");
+        let args = builder.build(FastbootCommand::GetVar("product".into()));
+        assert_eq!(args, vec!["-s", "R58M12345", "getvar", "product"]);
+    }
+
+    #[test]
+    fn test_reboot_bootloader_args() {
+        let cmd = FastbootCommand::Reboot {
+            mode: Some("bootloader".into()),
+        };
+        assert_eq!(cmd.to_args(), vec!["reboot", "bootloader"]);
+    }
+}
@@ -0,0 +1,86 @@
+// Fastboot Discovery - Bootloader device enumeration
+// Mirrors `adb::discovery`: parses `fastboot devices -l` output into typed device info. Devices
+// rebooted into the bootloader (e.g. via `reboot_device(mode = "bootloader")`) disappear from
+// `adb devices` and only show up here.
+
+use crate::fastboot::client::FastbootClient;
+use crate::error::AppError;
+
+/// A device seen by fastboot, i.e. sitting in the bootloader.
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct FastbootDeviceInfo {
+    pub serial: String,
+    /// USB bus location, e.g. `usb:1-1`, present when listing with `-l`.
+    pub usb_location: Option<String>,
+}
+
+/// Handles discovering devices sitting in the bootloader.
+pub struct FastbootDiscovery<'a> {
+    client: &'a FastbootClient,
+}
+
+impl<'a> FastbootDiscovery<'a> {
+    /// Create a new discovery instance using the provided client.
+    pub fn new(client: &'a FastbootClient) -> Self {
+        Self { client }
+    }
+
+    /// List all devices currently in the bootloader.
+    pub fn list_devices(&self) -> Result<Vec<FastbootDeviceInfo>, AppError> {
+        let output = self.client.execute(&["devices", "-l"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(Self::parse_devices_output(&stdout))
+    }
+
+    /// Parse the output of `fastboot devices -l`. Each line looks like
+    /// `<serial>    fastboot  usb:1-1`, or just `<serial>    fastboot` without `-l`.
+    pub(crate) fn parse_devices_output(output: &str) -> Vec<FastbootDeviceInfo> {
+        let mut devices = Vec::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 || parts[1] != "fastboot" {
+                continue;
+            }
+
+            devices.push(FastbootDeviceInfo {
+                serial: parts[0].to_string(),
+                usb_location: parts.get(2).map(|s| s.to_string()),
+            });
+        }
+
+        devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices_output_basic() {
+        let output = "R58M12345    fastboot\n";
+        let devices = FastbootDiscovery::parse_devices_output(output);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].serial, "R58M12345");
+        assert_eq!(devices[0].usb_location, None);
+    }
+
+    #[test]
+    fn test_parse_devices_output_with_usb_location() {
+        let output = "R58M12345    fastboot  usb:1-1\n";
+        let devices = FastbootDiscovery::parse_devices_output(output);
+        assert_eq!(devices[0].usb_location, Some("usb:1-1".to_string()));
+    }
+
+    #[test]
+    fn test_parse_devices_output_empty() {
+        let devices = FastbootDiscovery::parse_devices_output("");
+        assert!(devices.is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+// Fastboot Executor - Wraps fastboot command execution
+// Mirrors `adb::executor::AdbExecutor`: a facade over `FastbootClient`/`FastbootDiscovery`
+// providing a safe, typed interface for driving a device that's sitting in the bootloader.
+
+use crate::command_utils::hidden_command;
+use crate::error::{AppError, FastbootError};
+use crate::fastboot::client::FastbootClient;
+use crate::fastboot::command_builder::{FastbootCommand, FastbootCommandBuilder};
+use crate::fastboot::discovery::{FastbootDeviceInfo, FastbootDiscovery};
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+/// Timeout for a `boot` transfer, which can take much longer than a typical fastboot command on
+/// slow USB links. `flash` streams its output and waits for fastboot to exit without a timeout.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Progress callback for `flash`: receives each status line fastboot prints.
+pub type FlashProgressCallback<'a> = dyn FnMut(&str) + 'a;
+
+/// Executor for fastboot commands.
+pub struct FastbootExecutor {
+    client: FastbootClient,
+}
+
+impl Default for FastbootExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FastbootExecutor {
+    /// Create a new fastboot executor using the discovered fastboot path.
+    pub fn new() -> Self {
+        Self {
+            client: FastbootClient::new(),
+        }
+    }
+
+    /// Create a fastboot executor with a custom fastboot path.
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            client: FastbootClient::with_path(path),
+        }
+    }
+
+    /// Get the current path to the fastboot executable.
+    pub fn get_fastboot_path(&self) -> &PathBuf {
+        self.client.fastboot_path()
+    }
+
+    /// List all devices currently sitting in the bootloader.
+    pub fn list_devices(&self) -> Result<Vec<FastbootDeviceInfo>, AppError> {
+        let discovery = FastbootDiscovery::new(&self.client);
+        discovery.list_devices()
+    }
+
+    /// Read a `getvar` value (e.g. `product`, `current-slot`, `unlocked`) off the device.
+    /// fastboot reports `getvar` results on stderr as `<name>: <value>`.
+    pub fn get_var(&self, serial: &str, name: &str) -> Result<String, AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::GetVar(name.to_string()));
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let output = self.client.execute(&args_refs)?;
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stderr),
+            String::from_utf8_lossy(&output.stdout)
+        );
+
+        let prefix = format!("{}:", name);
+        combined
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(&prefix))
+            .map(|value| value.trim().to_string())
+            .ok_or_else(|| {
+                AppError::from(FastbootError::ParseError(format!(
+                    "getvar {} did not return a value",
+                    name
+                )))
+            })
+    }
+
+    /// Erase a partition.
+    pub fn erase(&self, serial: &str, partition: &str) -> Result<(), AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::Erase(partition.to_string()));
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let output = self.client.execute(&args_refs)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AppError::from(FastbootError::ExecutionFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )))
+        }
+    }
+
+    /// Temporarily boot an image without flashing it.
+    pub fn boot(&self, serial: &str, image_path: &str) -> Result<(), AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::Boot(image_path.to_string()));
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let output = self
+            .client
+            .execute_with_timeout(&args_refs, TRANSFER_TIMEOUT)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AppError::from(FastbootError::ExecutionFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )))
+        }
+    }
+
+    /// Set the active A/B slot.
+    pub fn set_active(&self, serial: &str, slot: &str) -> Result<(), AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::SetActive(slot.to_string()));
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let output = self.client.execute(&args_refs)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AppError::from(FastbootError::ExecutionFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )))
+        }
+    }
+
+    /// Reboot the device out of the bootloader, or into a specific mode (e.g. `bootloader` to
+    /// stay in fastboot, or `recovery`).
+    pub fn reboot(&self, serial: &str, mode: Option<&str>) -> Result<(), AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::Reboot {
+            mode: mode.map(|m| m.to_string()),
+        });
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        self.client.execute(&args_refs)?;
+        Ok(())
+    }
+
+    /// Flash an image to a partition, streaming fastboot's progress lines (e.g.
+    /// `Sending sparse 'boot' 1/1 ...`, `Writing 'boot'`) to `on_progress` rather than blocking
+    /// opaquely until the whole transfer completes.
+    pub fn flash(
+        &self,
+        serial: &str,
+        partition: &str,
+        image_path: &str,
+        mut on_progress: Option<&mut FlashProgressCallback>,
+    ) -> Result<(), AppError> {
+        let builder = FastbootCommandBuilder::new().target(serial);
+        let args = builder.build(FastbootCommand::Flash {
+            partition: partition.to_string(),
+            image: image_path.to_string(),
+        });
+
+        let mut child = hidden_command(self.client.fastboot_path())
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                AppError::from(FastbootError::ExecutionFailed(format!(
+                    "Failed to start fastboot flash: {}",
+                    e
+                )))
+            })?;
+
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| AppError::from(FastbootError::ExecutionFailed("Failed to capture fastboot output".into())))?;
+
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if let Some(cb) = on_progress.as_mut() {
+                cb(&line);
+            }
+        }
+
+        let status = child.wait().map_err(|e| {
+            AppError::from(FastbootError::ExecutionFailed(format!(
+                "Failed to wait on fastboot flash: {}",
+                e
+            )))
+        })?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(AppError::from(FastbootError::ExecutionFailed(format!(
+                "fastboot flash {} failed",
+                partition
+            ))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fastboot_executor_initialization() {
+        let executor = FastbootExecutor::new();
+        assert!(!executor.get_fastboot_path().as_os_str().is_empty());
+    }
+}
@@ -0,0 +1,13 @@
+// Fastboot Module - Handles all fastboot interactions
+// Mirrors the `adb` module tree so devices rebooted into the bootloader can still be managed:
+// discovery of fastboot devices plus a typed command builder and executor facade.
+
+pub mod client;
+pub mod command_builder;
+pub mod discovery;
+pub mod executor;
+
+pub use client::FastbootClient;
+pub use command_builder::{FastbootCommand, FastbootCommandBuilder};
+pub use discovery::{FastbootDeviceInfo, FastbootDiscovery};
+pub use executor::FastbootExecutor;
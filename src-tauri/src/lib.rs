@@ -6,73 +6,133 @@ pub mod apk;
 pub mod command_utils;
 pub mod commands;
 pub mod error;
+pub mod fastboot;
 pub mod requirements;
 pub mod services;
+pub mod signing;
 
-use adb::{start_device_tracker, AdbExecutor};
+use adb::{start_device_tracker, AdbExecutor, AgentManager, DeviceMonitorState};
 use commands::logcat::LogcatState;
+use commands::screen_capture::RecordingState;
+use commands::screen_mirror::ScreenMirrorState;
 use commands::{
+    // Agent
+    build_index,
+    capture_snapshot,
     check_action_requirements,
     check_adb_status,
+    check_apk_compatibility,
     check_device_requirements,
     clear_app_data,
     // Shell
     clear_logcat,
     // Wireless
+    adb_pair,
     connect_wireless,
     create_remote_directory,
     delete_remote_file,
     disconnect_wireless,
     enable_tcpip,
+    // Bootstrap
+    ensure_adb_available,
     execute_shell,
     export_logcat,
+    get_app_icon,
+    get_apps_full,
+    get_clipboard,
+    get_performance_stats,
+    // Fastboot
+    fastboot_boot,
+    fastboot_erase,
+    fastboot_flash,
+    fastboot_getvar,
+    fastboot_reboot,
+    fastboot_set_active,
+    // Recovery / Sideload Flow
+    flash_recovery_package,
+    get_battery_health,
     get_default_media_dir,
+    get_device_clipboard,
     get_device_ip,
     get_device_property,
     get_device_props,
     get_devices,
+    get_fastboot_devices,
     get_logcat,
+    get_logcat_backlog,
     get_scrcpy_status,
     // Screen Capture
     get_screen_frame,
     grant_all_permissions,
+    inject_tap_fast,
+    inject_text,
     input_tap,
     input_text,
     install_apk,
+    install_apk_split,
+    install_apk_streamed,
     kill_adb_server,
     // File Transfer
     list_files,
+    list_files_fast,
     list_packages,
     open_captures_folder,
+    pull_dir,
     pull_file,
+    pull_file_native,
+    push_dir,
     push_file,
     read_scrcpy_frame,
     // Device Actions
     reboot_device,
     refresh_devices,
+    report_client_stats,
     request_scrcpy_sync,
+    resolve_storage_path,
     save_capture_file,
     scan_apks_in_folder,
     scrcpy_key,
     scrcpy_scroll,
     scrcpy_text,
     scrcpy_touch,
+    search_files_fast,
     set_animations,
+    set_clipboard,
+    set_device_clipboard,
+    sideload_package,
+    // Signing
+    sign_apk,
+    sign_and_install,
+    is_apk_signed,
     // Quick Actions
     set_dark_mode,
     set_show_taps,
     start_adb_server,
+    // USB Hotplug Monitoring
+    start_device_monitor,
     // Logcat Streaming
     start_logcat_stream,
     // Scrcpy & Screen Capture
+    start_recording,
     start_scrcpy_server,
     start_screen_recording,
+    // Screen Mirror
+    start_screen_mirror,
+    // USB Hotplug Monitoring
+    stop_device_monitor,
     stop_logcat_stream,
+    stop_recording,
     stop_scrcpy_server,
     stop_screen_recording,
+    // Screen Mirror
+    stop_screen_mirror,
     take_screenshot,
+    // Agent
+    test_agent_connection,
     uninstall_app,
     validate_apk,
+    // Wipe
+    factory_reset,
 };
 use tauri::{Manager, RunEvent};
 
@@ -85,6 +145,10 @@ pub fn run() {
         .setup(|app| {
             // Manage state
             app.manage(LogcatState::new());
+            app.manage(DeviceMonitorState::new());
+            app.manage(ScreenMirrorState::new());
+            app.manage(RecordingState::new());
+            app.manage(AgentManager::new(AdbExecutor::new()));
 
             // Start real-time device tracking
             start_device_tracker(app.handle().clone());
@@ -97,10 +161,17 @@ pub fn run() {
             get_device_property,
             start_adb_server,
             kill_adb_server,
+            ensure_adb_available,
             check_device_requirements,
             check_action_requirements,
+            // USB Hotplug Monitoring
+            start_device_monitor,
+            stop_device_monitor,
+            check_apk_compatibility,
             validate_apk,
             install_apk,
+            install_apk_split,
+            install_apk_streamed,
             scan_apks_in_folder,
             // Device Actions
             reboot_device,
@@ -109,13 +180,19 @@ pub fn run() {
             uninstall_app,
             list_packages,
             get_device_props,
+            get_battery_health,
             // File Transfer
             list_files,
             push_file,
             pull_file,
+            push_dir,
+            pull_dir,
+            pull_file_native,
             delete_remote_file,
             create_remote_directory,
+            resolve_storage_path,
             // Wireless
+            adb_pair,
             connect_wireless,
             disconnect_wireless,
             enable_tcpip,
@@ -127,6 +204,7 @@ pub fn run() {
             // Logcat Streaming
             start_logcat_stream,
             stop_logcat_stream,
+            get_logcat_backlog,
             export_logcat,
             // Screen Capture
             take_screenshot,
@@ -136,6 +214,10 @@ pub fn run() {
             get_screen_frame,
             get_default_media_dir,
             open_captures_folder,
+            capture_snapshot,
+            // Screen Mirror
+            start_screen_mirror,
+            stop_screen_mirror,
             // Scrcpy
             start_scrcpy_server,
             stop_scrcpy_server,
@@ -146,12 +228,45 @@ pub fn run() {
             scrcpy_scroll,
             scrcpy_key,
             scrcpy_text,
+            inject_text,
+            set_device_clipboard,
+            get_device_clipboard,
+            start_recording,
+            stop_recording,
+            report_client_stats,
+            sideload_package,
+            flash_recovery_package,
+            // Fastboot
+            get_fastboot_devices,
+            fastboot_getvar,
+            fastboot_erase,
+            fastboot_boot,
+            fastboot_set_active,
+            fastboot_reboot,
+            fastboot_flash,
+            // Wipe
+            factory_reset,
+            // Signing
+            sign_apk,
+            sign_and_install,
+            is_apk_signed,
             // Quick Actions
             set_dark_mode,
             set_show_taps,
             set_animations,
             clear_app_data,
             grant_all_permissions,
+            // Agent
+            test_agent_connection,
+            get_apps_full,
+            get_app_icon,
+            list_files_fast,
+            get_performance_stats,
+            get_clipboard,
+            set_clipboard,
+            inject_tap_fast,
+            build_index,
+            search_files_fast,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -168,6 +283,22 @@ pub fn run() {
                     }
                 }
 
+                // Kill all screen mirror streams
+                if let Some(state) = app_handle.try_state::<ScreenMirrorState>() {
+                    let mut streams = state.streams.lock().unwrap();
+                    for (_, mut child) in streams
+                        .drain()
+                        .collect::<Vec<(String, std::process::Child)>>()
+                    {
+                        let _ = child.kill();
+                    }
+                }
+
+                // Stop any in-progress continuous screen recordings
+                if let Some(state) = app_handle.try_state::<RecordingState>() {
+                    state.stop_all();
+                }
+
                 // Kill ADB server when app closes to prevent orphan processes
                 let executor = AdbExecutor::new();
                 let _ = executor.kill_server();
@@ -178,4 +178,67 @@ impl<'a> RequirementChecker<'a> {
 
         checks
     }
+
+    /// Check whether an APK is compatible with a device before install, so users hit
+    /// `INSTALL_FAILED_OLDER_SDK`/`INSTALL_FAILED_NO_MATCHING_ABIS` (see `map_install_error`)
+    /// up front instead of after a failed `adb install`.
+    pub fn check_apk_compatibility(
+        &self,
+        device_id: &str,
+        apk: &crate::apk::ApkInfo,
+    ) -> Vec<RequirementCheck> {
+        let mut checks = Vec::new();
+
+        let sdk_version = RequirementCheck::new(
+            "apk_min_sdk",
+            "Android Version",
+            "Device must meet the app's minimum Android version",
+        );
+
+        match (
+            apk.min_sdk_version,
+            self.executor
+                .get_device_prop(device_id, "ro.build.version.sdk")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok()),
+        ) {
+            (Some(min_sdk), Some(device_sdk)) if min_sdk > device_sdk => {
+                checks.push(sdk_version.fail(&format!(
+                    "Device runs Android SDK {device_sdk}; app needs SDK {min_sdk}"
+                )));
+            }
+            _ => checks.push(sdk_version.pass()),
+        }
+
+        let abi_match = RequirementCheck::new(
+            "apk_abi_match",
+            "Device Architecture",
+            "App's native libraries must match one of the device's supported ABIs",
+        );
+
+        if apk.abis.is_empty() {
+            checks.push(abi_match.pass());
+        } else {
+            match self
+                .executor
+                .get_device_prop(device_id, "ro.product.cpu.abilist")
+            {
+                Ok(abilist) => {
+                    let device_abis: Vec<&str> = abilist.split(',').map(|a| a.trim()).collect();
+                    if apk.abis.iter().any(|a| device_abis.contains(&a.as_str())) {
+                        checks.push(abi_match.pass());
+                    } else {
+                        checks.push(abi_match.fail(&format!(
+                            "App is {}-only but device supports {}",
+                            apk.abis.join("/"),
+                            device_abis.join(", ")
+                        )));
+                    }
+                }
+                Err(_) => checks.push(abi_match.fail("Unable to read device CPU architecture")),
+            }
+        }
+
+        checks
+    }
 }
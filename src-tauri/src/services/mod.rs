@@ -0,0 +1,6 @@
+// Services Module - Long-running/stateful subsystems used by commands
+// (as opposed to commands/, which only holds the thin Tauri-facing wrappers)
+
+pub mod recorder;
+pub mod scrcpy;
+pub mod snapshot;
@@ -0,0 +1,678 @@
+// Session Recorder - tees the live scrcpy H.264 stream to disk as fragmented MP4 or Matroska
+// segments, independent of the live preview. Each segment is a self-contained, playable file
+// (ftyp+moov written up front for MP4, EBML header+Tracks for MKV) so a crash mid-recording
+// only loses the in-progress segment, not the whole recording.
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Roll over to a new segment file after this many milliseconds, aligned to the next keyframe.
+const SEGMENT_ROTATE_MS: u64 = 60_000;
+
+/// Start a new Matroska Cluster every time a keyframe lands past this many milliseconds, since
+/// SimpleBlock timecodes are relative 16-bit signed offsets from the owning Cluster.
+const MKV_CLUSTER_ROTATE_MS: u64 = 30_000;
+
+/// Container format for recorded segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    Mp4,
+    Mkv,
+}
+
+impl RecordingFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Mp4 => "mp4",
+            RecordingFormat::Mkv => "mkv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSegment {
+    pub path: String,
+    pub start_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordingManifest {
+    pub segments: Vec<RecordingSegment>,
+}
+
+enum SegmentWriter {
+    Mp4(Mp4Writer),
+    Mkv(MkvWriter),
+}
+
+impl SegmentWriter {
+    fn write_sample(&mut self, nal: &[u8], pts_ms: u64, is_keyframe: bool) -> std::io::Result<()> {
+        match self {
+            SegmentWriter::Mp4(w) => w.push_sample(nal.to_vec(), pts_ms, is_keyframe),
+            SegmentWriter::Mkv(w) => w.write_sample(nal, pts_ms, is_keyframe),
+        }
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        match self {
+            SegmentWriter::Mp4(w) => w.finish(),
+            SegmentWriter::Mkv(_) => Ok(()), // MKV clusters are self-delimiting; nothing to finalize
+        }
+    }
+}
+
+struct RecorderSession {
+    format: RecordingFormat,
+    dir: PathBuf,
+    base_name: String,
+    width: u32,
+    height: u32,
+    fps: u8,
+    sps: Option<Vec<u8>>,
+    pps: Option<Vec<u8>>,
+    writer: Option<SegmentWriter>,
+    segment_index: u32,
+    segment_start_ms: u64,
+    frame_index: u64,
+    manifest: RecordingManifest,
+}
+
+lazy_static::lazy_static! {
+    static ref RECORDERS: Mutex<HashMap<String, RecorderSession>> = Mutex::new(HashMap::new());
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Start teeing `device_id`'s live H.264 stream to segment files under `output_dir`.
+pub fn start_recording(
+    device_id: &str,
+    output_dir: &Path,
+    base_name: &str,
+    format: RecordingFormat,
+    width: u32,
+    height: u32,
+    fps: u8,
+) -> Result<(), AppError> {
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| AppError::new("RECORDING_DIR_ERROR", &format!("{}", e)))?;
+
+    let session = RecorderSession {
+        format,
+        dir: output_dir.to_path_buf(),
+        base_name: base_name.to_string(),
+        width,
+        height,
+        fps: fps.max(1),
+        sps: None,
+        pps: None,
+        writer: None,
+        segment_index: 0,
+        segment_start_ms: 0,
+        frame_index: 0,
+        manifest: RecordingManifest::default(),
+    };
+
+    RECORDERS
+        .lock()
+        .unwrap()
+        .insert(device_id.to_string(), session);
+
+    Ok(())
+}
+
+/// Stop recording `device_id`, flushing any buffered samples, and return the segment manifest.
+pub fn stop_recording(device_id: &str) -> Result<RecordingManifest, AppError> {
+    let mut sessions = RECORDERS.lock().unwrap();
+    let mut session = sessions
+        .remove(device_id)
+        .ok_or_else(|| AppError::new("NOT_RECORDING", "No recording in progress for device"))?;
+
+    if let Some(writer) = session.writer.as_mut() {
+        let _ = writer.finish();
+    }
+
+    Ok(session.manifest)
+}
+
+/// Feed one extracted NAL unit (with its Annex-B start code still attached) into the recorder
+/// for `device_id`, if a recording is active. Called from `decode_and_stream` alongside the
+/// existing SPS/PPS/IDR caching, so it's a no-op (and near-zero cost) when nothing is recording.
+pub(crate) fn tee_nal(device_id: &str, nal_data: &[u8], nal_type: u8) {
+    let mut sessions = RECORDERS.lock().unwrap();
+    let session = match sessions.get_mut(device_id) {
+        Some(session) => session,
+        None => return,
+    };
+
+    let payload = strip_start_code(nal_data);
+
+    match nal_type {
+        7 => session.sps = Some(payload.to_vec()),
+        8 => session.pps = Some(payload.to_vec()),
+        1 | 5 => {
+            let is_keyframe = nal_type == 5;
+            if is_keyframe {
+                maybe_rotate_segment(session);
+            }
+
+            if session.writer.is_none() {
+                // Can't start a segment without SPS/PPS (for the moov/Tracks) or before the
+                // first keyframe (mid-GOP video isn't independently decodable).
+                return;
+            }
+
+            let pts_ms = session.frame_index * 1000 / session.fps as u64;
+            session.frame_index += 1;
+
+            if let Some(writer) = session.writer.as_mut() {
+                let _ = writer.write_sample(payload, pts_ms, is_keyframe);
+            }
+        }
+        _ => {} // AUD/SEI/etc. aren't needed for playback
+    }
+}
+
+fn maybe_rotate_segment(session: &mut RecorderSession) {
+    let (sps, pps) = match (&session.sps, &session.pps) {
+        (Some(sps), Some(pps)) => (sps.clone(), pps.clone()),
+        _ => return, // wait for the next keyframe once we've cached SPS/PPS
+    };
+
+    let elapsed = now_ms().saturating_sub(session.segment_start_ms);
+    if session.writer.is_some() && elapsed < SEGMENT_ROTATE_MS {
+        return;
+    }
+
+    if let Some(writer) = session.writer.as_mut() {
+        let _ = writer.finish();
+    }
+
+    session.segment_index += 1;
+    let path = session.dir.join(format!(
+        "{}_{:04}.{}",
+        session.base_name,
+        session.segment_index,
+        session.format.extension()
+    ));
+
+    let writer = match session.format {
+        RecordingFormat::Mp4 => Mp4Writer::new(&path, &sps, &pps, session.width, session.height)
+            .map(SegmentWriter::Mp4),
+        RecordingFormat::Mkv => MkvWriter::new(&path, &sps, &pps, session.width, session.height)
+            .map(SegmentWriter::Mkv),
+    };
+
+    match writer {
+        Ok(writer) => {
+            let start_ms = now_ms();
+            session.segment_start_ms = start_ms;
+            session.frame_index = 0;
+            session.writer = Some(writer);
+            session.manifest.segments.push(RecordingSegment {
+                path: path.to_string_lossy().to_string(),
+                start_ms,
+            });
+        }
+        Err(_) => {
+            // Couldn't open the next segment file; keep recording attempts going on the next
+            // keyframe rather than aborting the whole session.
+        }
+    }
+}
+
+/// Strip the Annex-B start code (00 00 01 or 00 00 00 01) a NAL unit was extracted with, leaving
+/// the NAL header byte and RBSP payload as required by both avcC/avcc sample data and the MKV
+/// SimpleBlock format this writer uses.
+fn strip_start_code(nal: &[u8]) -> &[u8] {
+    if nal.len() > 4 && nal[0] == 0 && nal[1] == 0 && nal[2] == 0 && nal[3] == 1 {
+        &nal[4..]
+    } else if nal.len() > 3 && nal[0] == 0 && nal[1] == 0 && nal[2] == 1 {
+        &nal[3..]
+    } else {
+        nal
+    }
+}
+
+fn build_avc_decoder_config(sps: &[u8], pps: &[u8]) -> Vec<u8> {
+    let mut avcc = Vec::with_capacity(11 + sps.len() + pps.len());
+    avcc.push(1); // configurationVersion
+    avcc.push(sps.get(1).copied().unwrap_or(0)); // AVCProfileIndication
+    avcc.push(sps.get(2).copied().unwrap_or(0)); // profile_compatibility
+    avcc.push(sps.get(3).copied().unwrap_or(0)); // AVCLevelIndication
+    avcc.push(0xFF); // reserved(6) + lengthSizeMinusOne=3 (4-byte lengths)
+    avcc.push(0xE1); // reserved(3) + numOfSequenceParameterSets=1
+    avcc.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+    avcc.extend_from_slice(sps);
+    avcc.push(1); // numOfPictureParameterSets
+    avcc.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+    avcc.extend_from_slice(pps);
+    avcc
+}
+
+// ---------------------------------------------------------------------------------------------
+// Fragmented MP4 writer
+// ---------------------------------------------------------------------------------------------
+
+fn iso_box(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8 + payload.len());
+    buf.extend_from_slice(&((8 + payload.len()) as u32).to_be_bytes());
+    buf.extend_from_slice(fourcc);
+    buf.extend_from_slice(payload);
+    buf
+}
+
+fn build_ftyp() -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(b"isom"); // major_brand
+    payload.extend_from_slice(&0u32.to_be_bytes()); // minor_version
+    for brand in [b"isom", b"iso5", b"avc1", b"mp41"] {
+        payload.extend_from_slice(brand);
+    }
+    iso_box(b"ftyp", &payload)
+}
+
+fn build_moov(sps: &[u8], pps: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mvhd = {
+        let mut p = vec![0u8; 100];
+        p[12..16].copy_from_slice(&1000u32.to_be_bytes()); // timescale (ms)
+        p[20..24].copy_from_slice(&0x00010000u32.to_be_bytes()); // rate 1.0
+        p[24..26].copy_from_slice(&0x0100u16.to_be_bytes()); // volume 1.0
+        // identity matrix
+        for (i, v) in [0x00010000u32, 0, 0, 0, 0x00010000, 0, 0, 0, 0x40000000]
+            .iter()
+            .enumerate()
+        {
+            p[36 + i * 4..40 + i * 4].copy_from_slice(&v.to_be_bytes());
+        }
+        p[96..100].copy_from_slice(&2u32.to_be_bytes()); // next_track_ID
+        iso_box(b"mvhd", &p)
+    };
+
+    let tkhd = {
+        let mut p = vec![0u8; 84];
+        p[0] = 0; // version
+        p[3] = 0x07; // flags: track enabled + in movie + in preview
+        p[12..16].copy_from_slice(&1u32.to_be_bytes()); // track_ID
+        for (i, v) in [0x00010000u32, 0, 0, 0, 0x00010000, 0, 0, 0, 0x40000000]
+            .iter()
+            .enumerate()
+        {
+            p[40 + i * 4..44 + i * 4].copy_from_slice(&v.to_be_bytes());
+        }
+        p[76..80].copy_from_slice(&(width << 16).to_be_bytes());
+        p[80..84].copy_from_slice(&(height << 16).to_be_bytes());
+        iso_box(b"tkhd", &p)
+    };
+
+    let mdhd = {
+        let mut p = vec![0u8; 24];
+        p[12..16].copy_from_slice(&1000u32.to_be_bytes()); // timescale (ms)
+        p[20..22].copy_from_slice(&0x55c4u16.to_be_bytes()); // language "und"
+        iso_box(b"mdhd", &p)
+    };
+
+    let hdlr = {
+        let mut p = vec![0u8; 24];
+        p[8..12].copy_from_slice(b"vide");
+        p.extend_from_slice(b"VideoHandler\0");
+        iso_box(b"hdlr", &p)
+    };
+
+    let vmhd = {
+        let p = vec![0u8; 12];
+        iso_box(b"vmhd", &p)
+    };
+    let dref = {
+        let mut p = vec![0u8; 8];
+        p[7] = 1; // entry_count = 1
+        p.extend_from_slice(&iso_box(b"url ", &[0, 0, 0, 1]));
+        iso_box(b"dref", &p)
+    };
+    let dinf = iso_box(b"dinf", &dref);
+
+    let avc_config = build_avc_decoder_config(sps, pps);
+    let avcc = iso_box(b"avcC", &avc_config);
+    let avc1 = {
+        let mut p = vec![0u8; 78];
+        p[7] = 1; // data_reference_index
+        p[24..26].copy_from_slice(&(width as u16).to_be_bytes());
+        p[26..28].copy_from_slice(&(height as u16).to_be_bytes());
+        p[28..32].copy_from_slice(&0x00480000u32.to_be_bytes()); // horizresolution 72dpi
+        p[32..36].copy_from_slice(&0x00480000u32.to_be_bytes()); // vertresolution 72dpi
+        p[40..42].copy_from_slice(&1u16.to_be_bytes()); // frame_count
+        p[74..76].copy_from_slice(&0x0018u16.to_be_bytes()); // depth 24
+        p[76..78].copy_from_slice(&0xFFFFu16.to_be_bytes()); // pre_defined -1
+        p.extend_from_slice(&avcc);
+        iso_box(b"avc1", &p)
+    };
+    let stsd = {
+        let mut p = vec![0u8; 8];
+        p[7] = 1; // entry_count
+        p.extend_from_slice(&avc1);
+        iso_box(b"stsd", &p)
+    };
+
+    // Empty sample tables: sample info lives in per-fragment moof/traf, not here.
+    let stts = iso_box(b"stts", &[0, 0, 0, 0, 0, 0, 0, 0]);
+    let stsc = iso_box(b"stsc", &[0, 0, 0, 0, 0, 0, 0, 0]);
+    let stsz = iso_box(b"stsz", &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let stco = iso_box(b"stco", &[0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut stbl_payload = Vec::new();
+    stbl_payload.extend_from_slice(&stsd);
+    stbl_payload.extend_from_slice(&stts);
+    stbl_payload.extend_from_slice(&stsc);
+    stbl_payload.extend_from_slice(&stsz);
+    stbl_payload.extend_from_slice(&stco);
+    let stbl = iso_box(b"stbl", &stbl_payload);
+
+    let mut minf_payload = Vec::new();
+    minf_payload.extend_from_slice(&vmhd);
+    minf_payload.extend_from_slice(&dinf);
+    minf_payload.extend_from_slice(&stbl);
+    let minf = iso_box(b"minf", &minf_payload);
+
+    let mut mdia_payload = Vec::new();
+    mdia_payload.extend_from_slice(&mdhd);
+    mdia_payload.extend_from_slice(&hdlr);
+    mdia_payload.extend_from_slice(&minf);
+    let mdia = iso_box(b"mdia", &mdia_payload);
+
+    let mut trak_payload = Vec::new();
+    trak_payload.extend_from_slice(&tkhd);
+    trak_payload.extend_from_slice(&mdia);
+    let trak = iso_box(b"trak", &trak_payload);
+
+    let trex = {
+        let mut p = vec![0u8; 24];
+        p[4..8].copy_from_slice(&1u32.to_be_bytes()); // track_ID
+        p[8..12].copy_from_slice(&1u32.to_be_bytes()); // default_sample_description_index
+        iso_box(b"trex", &p)
+    };
+    let mvex = iso_box(b"mvex", &trex);
+
+    let mut moov_payload = Vec::new();
+    moov_payload.extend_from_slice(&mvhd);
+    moov_payload.extend_from_slice(&trak);
+    moov_payload.extend_from_slice(&mvex);
+    iso_box(b"moov", &moov_payload)
+}
+
+struct Mp4Writer {
+    file: File,
+    sequence_number: u32,
+    base_media_decode_time: u64,
+    samples: Vec<(Vec<u8>, u64, bool)>, // (nal, pts_ms, is_keyframe)
+}
+
+const TRUN_FLAGS: u32 = 0x000001 | 0x000100 | 0x000200 | 0x000400; // data-offset, duration, size, flags
+
+impl Mp4Writer {
+    fn new(path: &Path, sps: &[u8], pps: &[u8], width: u32, height: u32) -> std::io::Result<Self> {
+        let mut file = File::create(path)?;
+        file.write_all(&build_ftyp())?;
+        file.write_all(&build_moov(sps, pps, width, height))?;
+        Ok(Self {
+            file,
+            sequence_number: 0,
+            base_media_decode_time: 0,
+            samples: Vec::new(),
+        })
+    }
+
+    fn push_sample(&mut self, nal: Vec<u8>, pts_ms: u64, is_keyframe: bool) -> std::io::Result<()> {
+        if is_keyframe && !self.samples.is_empty() {
+            self.flush_fragment()?;
+        }
+        self.samples.push((nal, pts_ms, is_keyframe));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.flush_fragment()
+    }
+
+    fn flush_fragment(&mut self) -> std::io::Result<()> {
+        if self.samples.is_empty() {
+            return Ok(());
+        }
+
+        let samples = std::mem::take(&mut self.samples);
+        self.sequence_number += 1;
+
+        // Per-sample duration is the delta to the next sample's PTS; the last sample in the
+        // fragment reuses the previous delta (or a nominal 1-frame default if there's only one).
+        let mut durations = Vec::with_capacity(samples.len());
+        for i in 0..samples.len() {
+            let d = if i + 1 < samples.len() {
+                samples[i + 1].1.saturating_sub(samples[i].1)
+            } else if i > 0 {
+                samples[i].1.saturating_sub(samples[i - 1].1)
+            } else {
+                1000 / 30
+            };
+            durations.push(d.max(1) as u32);
+        }
+
+        let mfhd = {
+            let mut p = vec![0u8; 8];
+            p[4..8].copy_from_slice(&self.sequence_number.to_be_bytes());
+            iso_box(b"mfhd", &p)
+        };
+
+        let tfhd = {
+            let mut p = vec![0u8; 8];
+            p[1] = 0x02; // flags (3 bytes, big-endian): default-base-is-moof = 0x020000
+            p[4..8].copy_from_slice(&1u32.to_be_bytes()); // track_ID
+            iso_box(b"tfhd", &p)
+        };
+
+        let tfdt = {
+            let mut p = vec![0u8; 12];
+            p[0] = 1; // version 1 -> 64-bit baseMediaDecodeTime
+            p[4..12].copy_from_slice(&self.base_media_decode_time.to_be_bytes());
+            iso_box(b"tfdt", &p)
+        };
+
+        let trun_payload_len = 12 + samples.len() * 12;
+        let mfhd_len = mfhd.len();
+        let tfhd_len = tfhd.len();
+        let tfdt_len = tfdt.len();
+        let trun_len = 8 + trun_payload_len;
+        let traf_len = 8 + tfhd_len + tfdt_len + trun_len;
+        let moof_len = 8 + mfhd_len + traf_len;
+        let data_offset = (moof_len + 8) as i32; // + mdat header
+
+        let trun = {
+            let mut p = Vec::with_capacity(trun_payload_len);
+            p.extend_from_slice(&TRUN_FLAGS.to_be_bytes());
+            p.extend_from_slice(&(samples.len() as u32).to_be_bytes());
+            p.extend_from_slice(&data_offset.to_be_bytes());
+            for (i, (nal, _, is_key)) in samples.iter().enumerate() {
+                p.extend_from_slice(&durations[i].to_be_bytes());
+                p.extend_from_slice(&(nal.len() as u32 + 4).to_be_bytes());
+                let flags: u32 = if *is_key { 0x02000000 } else { 0x01010000 };
+                p.extend_from_slice(&flags.to_be_bytes());
+            }
+            iso_box(b"trun", &p)
+        };
+
+        let mut traf_payload = Vec::with_capacity(tfhd_len + tfdt_len + trun.len());
+        traf_payload.extend_from_slice(&tfhd);
+        traf_payload.extend_from_slice(&tfdt);
+        traf_payload.extend_from_slice(&trun);
+        let traf = iso_box(b"traf", &traf_payload);
+
+        let mut moof_payload = Vec::with_capacity(mfhd_len + traf.len());
+        moof_payload.extend_from_slice(&mfhd);
+        moof_payload.extend_from_slice(&traf);
+        let moof = iso_box(b"moof", &moof_payload);
+
+        let mut mdat_payload = Vec::new();
+        for (nal, _, _) in &samples {
+            mdat_payload.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+            mdat_payload.extend_from_slice(nal);
+        }
+        let mdat = iso_box(b"mdat", &mdat_payload);
+
+        self.base_media_decode_time += durations.iter().map(|d| *d as u64).sum::<u64>();
+
+        self.file.write_all(&moof)?;
+        self.file.write_all(&mdat)
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// Matroska (MKV) writer
+// ---------------------------------------------------------------------------------------------
+
+const EBML_HEADER_ID: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+const SEGMENT_ID: [u8; 4] = [0x18, 0x53, 0x80, 0x67];
+const INFO_ID: [u8; 4] = [0x15, 0x49, 0xA9, 0x66];
+const TIMECODE_SCALE_ID: [u8; 3] = [0x2A, 0xD7, 0xB1];
+const TRACKS_ID: [u8; 4] = [0x16, 0x54, 0xAE, 0x6B];
+const TRACK_ENTRY_ID: u8 = 0xAE;
+const TRACK_NUMBER_ID: u8 = 0xD7;
+const TRACK_UID_ID: [u8; 2] = [0x73, 0xC5];
+const TRACK_TYPE_ID: u8 = 0x83;
+const CODEC_ID_ID: u8 = 0x86;
+const CODEC_PRIVATE_ID: [u8; 2] = [0x63, 0xA2];
+const VIDEO_ID: u8 = 0xE0;
+const PIXEL_WIDTH_ID: u8 = 0xB0;
+const PIXEL_HEIGHT_ID: u8 = 0xBA;
+const CLUSTER_ID: [u8; 4] = [0x1F, 0x43, 0xB6, 0x75];
+const TIMECODE_ID: u8 = 0xE7;
+const SIMPLE_BLOCK_ID: u8 = 0xA3;
+
+/// All-1s content with the size marker, i.e. EBML's "unknown size" sentinel for live/streamed
+/// elements we never seek back to patch (Segment, Cluster) - standard practice for muxers that
+/// must stay valid if the process dies mid-write.
+const UNKNOWN_SIZE_8: [u8; 8] = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+
+fn ebml_size(value: u64) -> Vec<u8> {
+    for width in 1..=8u32 {
+        let max = (1u64 << (7 * width)) - 1;
+        if value < max {
+            let mut bytes = vec![0u8; width as usize];
+            let mut v = value;
+            for i in (0..width as usize).rev() {
+                bytes[i] = (v & 0xFF) as u8;
+                v >>= 8;
+            }
+            bytes[0] |= 1 << (8 - width);
+            return bytes;
+        }
+    }
+    UNKNOWN_SIZE_8.to_vec()
+}
+
+fn ebml_uint(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first_nonzero = bytes.iter().position(|b| *b != 0).unwrap_or(7);
+    bytes[first_nonzero..].to_vec()
+}
+
+fn ebml_elem(id: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(id.len() + 9 + payload.len());
+    buf.extend_from_slice(id);
+    buf.extend_from_slice(&ebml_size(payload.len() as u64));
+    buf.extend_from_slice(payload);
+    buf
+}
+
+fn build_ebml_header() -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0x86], &ebml_uint(1))); // EBMLVersion
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0xF7], &ebml_uint(1))); // EBMLReadVersion
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0xF2], &ebml_uint(4))); // EBMLMaxIDLength
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0xF3], &ebml_uint(8))); // EBMLMaxSizeLength
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0x82], b"matroska")); // DocType
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0x87], &ebml_uint(4))); // DocTypeVersion
+    payload.extend_from_slice(&ebml_elem(&[0x42, 0x85], &ebml_uint(2))); // DocTypeReadVersion
+    ebml_elem(&EBML_HEADER_ID, &payload)
+}
+
+fn build_segment_info() -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&ebml_elem(&TIMECODE_SCALE_ID, &ebml_uint(1_000_000))); // ns per tick -> 1ms ticks
+    ebml_elem(&INFO_ID, &payload)
+}
+
+fn build_tracks(sps: &[u8], pps: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let video = {
+        let mut p = Vec::new();
+        p.extend_from_slice(&ebml_elem(&[PIXEL_WIDTH_ID], &ebml_uint(width as u64)));
+        p.extend_from_slice(&ebml_elem(&[PIXEL_HEIGHT_ID], &ebml_uint(height as u64)));
+        ebml_elem(&[VIDEO_ID], &p)
+    };
+
+    let avcc = build_avc_decoder_config(sps, pps);
+
+    let mut entry = Vec::new();
+    entry.extend_from_slice(&ebml_elem(&[TRACK_NUMBER_ID], &ebml_uint(1)));
+    entry.extend_from_slice(&ebml_elem(&TRACK_UID_ID, &ebml_uint(1)));
+    entry.extend_from_slice(&ebml_elem(&[TRACK_TYPE_ID], &ebml_uint(1))); // video
+    entry.extend_from_slice(&ebml_elem(&[CODEC_ID_ID], b"V_MPEG4/ISO/AVC"));
+    entry.extend_from_slice(&ebml_elem(&CODEC_PRIVATE_ID, &avcc));
+    entry.extend_from_slice(&video);
+    let track_entry = ebml_elem(&[TRACK_ENTRY_ID], &entry);
+
+    ebml_elem(&TRACKS_ID, &track_entry)
+}
+
+struct MkvWriter {
+    file: File,
+    cluster_start_ms: u64,
+}
+
+impl MkvWriter {
+    fn new(path: &Path, sps: &[u8], pps: &[u8], width: u32, height: u32) -> std::io::Result<Self> {
+        let mut file = File::create(path)?;
+        file.write_all(&build_ebml_header())?;
+        file.write_all(&SEGMENT_ID)?;
+        file.write_all(&UNKNOWN_SIZE_8)?;
+        file.write_all(&build_segment_info())?;
+        file.write_all(&build_tracks(sps, pps, width, height))?;
+        file.write_all(&CLUSTER_ID)?;
+        file.write_all(&UNKNOWN_SIZE_8)?;
+        file.write_all(&ebml_elem(&[TIMECODE_ID], &ebml_uint(0)))?;
+        Ok(Self {
+            file,
+            cluster_start_ms: 0,
+        })
+    }
+
+    fn write_sample(&mut self, nal: &[u8], pts_ms: u64, is_keyframe: bool) -> std::io::Result<()> {
+        if is_keyframe && pts_ms.saturating_sub(self.cluster_start_ms) >= MKV_CLUSTER_ROTATE_MS {
+            // A sibling Cluster ID at the Segment level implicitly closes the previous
+            // unknown-size Cluster, per the EBML spec.
+            self.file.write_all(&CLUSTER_ID)?;
+            self.file.write_all(&UNKNOWN_SIZE_8)?;
+            self.file
+                .write_all(&ebml_elem(&[TIMECODE_ID], &ebml_uint(pts_ms)))?;
+            self.cluster_start_ms = pts_ms;
+        }
+
+        let relative = (pts_ms - self.cluster_start_ms) as i16;
+        let mut block = Vec::with_capacity(4 + nal.len());
+        block.extend_from_slice(&ebml_size(1)); // track number
+        block.extend_from_slice(&relative.to_be_bytes());
+        block.push(if is_keyframe { 0x80 } else { 0x00 }); // flags: keyframe bit
+        block.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+        block.extend_from_slice(nal);
+
+        self.file.write_all(&ebml_elem(&[SIMPLE_BLOCK_ID], &block))
+    }
+}
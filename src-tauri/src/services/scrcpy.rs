@@ -19,6 +19,20 @@ const SCRCPY_SERVER_VERSION: &str = "2.7";
 
 const TARGET_FPS: u32 = 30;
 
+/// Reject a frame-meta length bigger than this before allocating its buffer. A single H.264
+/// access unit this large would already mean something is very wrong with the stream; this
+/// exists to stop a corrupt or hostile length field from turning into an unbounded allocation.
+const MAX_VIDEO_PAYLOAD_SIZE: usize = 32 * 1024 * 1024;
+
+/// Same idea as `MAX_VIDEO_PAYLOAD_SIZE`, but for a single compressed audio packet, which is
+/// always far smaller than a video frame.
+const MAX_AUDIO_PAYLOAD_SIZE: usize = 4 * 1024 * 1024;
+
+/// Same idea again, but for a single clipboard text message - clipboard content never needs to
+/// approach this size, so it exists only to stop a hostile or corrupt length field from turning
+/// into an unbounded allocation.
+const MAX_CLIPBOARD_PAYLOAD_SIZE: usize = 1024 * 1024;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ScrcpyConfig {
     pub max_size: u32,
@@ -34,6 +48,10 @@ pub struct ScrcpyConfig {
     pub power_off_on_close: bool,
     pub cleanup: bool,
     pub power_on: bool,
+    /// Mirror device audio alongside video over a third forwarded socket.
+    pub audio: bool,
+    /// scrcpy audio codec name: "opus", "aac", or "raw".
+    pub audio_codec: String,
 }
 
 impl Default for ScrcpyConfig {
@@ -44,7 +62,7 @@ impl Default for ScrcpyConfig {
             max_fps: TARGET_FPS as u8,
             lock_video_orientation: -1,
             tunnel_forward: true,
-            send_frame_meta: false,
+            send_frame_meta: true,
             control: true,
             display_id: 0,
             show_touches: false,
@@ -52,6 +70,8 @@ impl Default for ScrcpyConfig {
             power_off_on_close: false,
             cleanup: true,
             power_on: true,
+            audio: false,
+            audio_codec: "opus".to_string(),
         }
     }
 }
@@ -62,6 +82,26 @@ pub struct ScrcpyStatus {
     pub device_id: Option<String>,
     pub port: Option<u16>,
     pub codec_info: Option<Vec<u8>>,
+    /// Negotiated audio codec ("opus"/"aac"/"raw"), or `None` if audio mirroring is off.
+    pub audio_codec: Option<String>,
+    /// Fixed sample rate scrcpy uses for Opus; `None` for AAC, which carries its own rate in the
+    /// ASC config packet.
+    pub audio_sample_rate: Option<u32>,
+    pub audio_channels: Option<u8>,
+    /// Current adaptive-bitrate target, which may have stepped down from what was requested.
+    pub current_bit_rate: Option<u32>,
+    /// Measured video throughput over the last full second, for frontend backpressure UI.
+    pub bytes_per_sec: Option<u64>,
+}
+
+/// Structured H.264 access-unit event carried by `scrcpy-frame-{id}` and `scrcpy-sync-*`, decoded
+/// from scrcpy's 12-byte frame-meta header (8-byte PTS/flags + 4-byte length).
+#[derive(Clone, Serialize)]
+pub struct ScrcpyFramePayload {
+    pub data: String,
+    pub pts_us: u64,
+    pub is_config: bool,
+    pub is_keyframe: bool,
 }
 
 struct ScrcpySession {
@@ -74,6 +114,97 @@ struct ScrcpySession {
     last_sps: Arc<Mutex<Option<Vec<u8>>>>,
     last_pps: Arc<Mutex<Option<Vec<u8>>>>,
     last_idr: Arc<Mutex<Option<Vec<u8>>>>,
+    audio_codec: Option<String>,
+    audio_sample_rate: Option<u32>,
+    audio_channels: Option<u8>,
+    /// The config the server is currently running with, kept around so an adaptive-bitrate
+    /// restart can clone it and only touch `bit_rate`/`max_size`.
+    config: ScrcpyConfig,
+    bitrate: Arc<Mutex<BitrateController>>,
+    stream_stats: Arc<Mutex<StreamStats>>,
+}
+
+/// Minimum bitrate the adaptive controller will step down to before it starts shrinking
+/// `max_size` instead.
+const MIN_BIT_RATE: u32 = 500_000;
+/// Floor for `max_size` once even the minimum bitrate isn't enough.
+const MIN_MAX_SIZE: u32 = 240;
+const BITRATE_STEP_DOWN: f64 = 0.7;
+const BITRATE_STEP_UP: f64 = 1.15;
+/// Client-reported decode latency above this is considered unhealthy.
+const LATENCY_UNHEALTHY_MS: u32 = 150;
+/// Self-measured inter-frame jitter above this is considered unhealthy.
+const JITTER_UNHEALTHY_MS: f64 = 200.0;
+/// Consecutive healthy stats reports required before stepping the bitrate back up.
+const HEALTHY_STREAK_TO_STEP_UP: u32 = 8;
+/// Minimum time between two restarts, so a burst of stats reports doesn't thrash the encoder.
+const ADJUST_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// Runtime state for the adaptive bitrate controller (ALVR-style step up/down on stream health).
+#[derive(Clone)]
+struct BitrateController {
+    ceiling_bit_rate: u32,
+    current_bit_rate: u32,
+    healthy_streak: u32,
+    last_adjusted_at: std::time::Instant,
+}
+
+impl BitrateController {
+    fn new(initial_bit_rate: u32) -> Self {
+        Self {
+            ceiling_bit_rate: initial_bit_rate,
+            current_bit_rate: initial_bit_rate,
+            healthy_streak: 0,
+            last_adjusted_at: std::time::Instant::now(),
+        }
+    }
+}
+
+/// Per-second throughput and inter-frame jitter, sampled from the raw bytes flowing through
+/// `decode_and_stream` (independent of whatever the frontend later reports back).
+struct StreamStats {
+    window_start: std::time::Instant,
+    bytes_in_window: u64,
+    bytes_per_sec: u64,
+    last_frame_at: Option<std::time::Instant>,
+    avg_interval_ms: f64,
+    jitter_ms_ema: f64,
+}
+
+impl StreamStats {
+    fn new() -> Self {
+        Self {
+            window_start: std::time::Instant::now(),
+            bytes_in_window: 0,
+            bytes_per_sec: 0,
+            last_frame_at: None,
+            avg_interval_ms: 0.0,
+            jitter_ms_ema: 0.0,
+        }
+    }
+
+    fn record_frame(&mut self, payload_len: usize) {
+        let now = std::time::Instant::now();
+
+        self.bytes_in_window += payload_len as u64;
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.bytes_per_sec = self.bytes_in_window;
+            self.bytes_in_window = 0;
+            self.window_start = now;
+        }
+
+        if let Some(last) = self.last_frame_at {
+            let interval_ms = now.duration_since(last).as_secs_f64() * 1000.0;
+            if self.avg_interval_ms == 0.0 {
+                self.avg_interval_ms = interval_ms;
+            } else {
+                self.avg_interval_ms = self.avg_interval_ms * 0.9 + interval_ms * 0.1;
+            }
+            let deviation = (interval_ms - self.avg_interval_ms).abs();
+            self.jitter_ms_ema = self.jitter_ms_ema * 0.9 + deviation * 0.1;
+        }
+        self.last_frame_at = Some(now);
+    }
 }
 
 lazy_static::lazy_static! {
@@ -207,7 +338,7 @@ pub fn start_server(
         lock_video_orientation={} tunnel_forward={} \
         send_frame_meta={} control=true display_id={} \
         show_touches={} stay_awake={} power_off_on_close={} \
-        cleanup={} power_on={} audio=false video=true",
+        cleanup={} power_on={} audio={} audio_codec={} video=true",
         SCRCPY_SERVER_PATH,
         SCRCPY_SERVER_VERSION, // "2.7"
         config.max_size,
@@ -221,6 +352,8 @@ pub fn start_server(
         config.power_off_on_close,
         config.cleanup,
         config.power_on,
+        config.audio,
+        config.audio_codec,
     );
 
     let mut server_process = hidden_command(&adb_path)
@@ -280,6 +413,24 @@ pub fn start_server(
         .ok();
     video_socket.set_nodelay(true).ok();
 
+    // Connect to audio socket (server accepts connections in order: video, audio, control)
+    let audio_socket = if config.audio {
+        let mut socket: Option<TcpStream> = None;
+        for _ in 0..5 {
+            if let Ok(s) = TcpStream::connect(format!("127.0.0.1:{}", video_port)) {
+                s.set_nodelay(true).ok();
+                socket = Some(s);
+                break;
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        Some(socket.ok_or_else(|| {
+            AppError::new("AUDIO_SOCKET_ERROR", "Failed to connect audio socket")
+        })?)
+    } else {
+        None
+    };
+
     // Connect to control socket
     let mut control_socket: Option<TcpStream> = None;
     for _ in 0..5 {
@@ -294,8 +445,28 @@ pub fn start_server(
     let control_socket = control_socket
         .ok_or_else(|| AppError::new("CONTROL_SOCKET_ERROR", "Failed to connect control socket"))?;
 
+    // Independent handle sharing the same fd, so the reader thread below can block on reads
+    // while commands keep writing through the Arc<Mutex<TcpStream>> stored on the session.
+    let control_socket_reader = control_socket.try_clone().map_err(|e| {
+        AppError::new(
+            "CONTROL_SOCKET_ERROR",
+            &format!("Failed to clone control socket: {}", e),
+        )
+    })?;
+
     let streaming = Arc::new(Mutex::new(true));
 
+    let (audio_codec, audio_sample_rate, audio_channels) = if config.audio {
+        let (rate, channels) = if config.audio_codec == "opus" {
+            (Some(48_000), Some(2))
+        } else {
+            (None, None)
+        };
+        (Some(config.audio_codec.clone()), rate, channels)
+    } else {
+        (None, None, None)
+    };
+
     // Store session
     let session = ScrcpySession {
         server_process: Some(server_process),
@@ -306,11 +477,18 @@ pub fn start_server(
         last_sps: Arc::new(Mutex::new(None)),
         last_pps: Arc::new(Mutex::new(None)),
         last_idr: Arc::new(Mutex::new(None)),
+        audio_codec: audio_codec.clone(),
+        audio_sample_rate,
+        audio_channels,
+        config: config.clone(),
+        bitrate: Arc::new(Mutex::new(BitrateController::new(config.bit_rate))),
+        stream_stats: Arc::new(Mutex::new(StreamStats::new())),
     };
 
     let last_sps = session.last_sps.clone();
     let last_pps = session.last_pps.clone();
     let last_idr = session.last_idr.clone();
+    let stream_stats = session.stream_stats.clone();
 
     {
         let mut sessions = SCRCPY_SESSIONS.lock().unwrap();
@@ -320,24 +498,61 @@ pub fn start_server(
     // Start decode/stream thread
     let device_id_clone = device_id.to_string();
     let app_handle_clone = app_handle.clone();
+    let video_streaming = streaming.clone();
 
     thread::spawn(move || {
         decode_and_stream(
             device_id_clone,
             video_socket,
-            streaming,
+            video_streaming,
             app_handle_clone,
             last_sps,
             last_pps,
             last_idr,
+            stream_stats,
         );
     });
 
+    if let Some(audio_socket) = audio_socket {
+        let device_id_clone = device_id.to_string();
+        let app_handle_clone = app_handle.clone();
+        let audio_streaming = streaming.clone();
+
+        thread::spawn(move || {
+            audio_decode_and_stream(
+                device_id_clone,
+                audio_socket,
+                audio_streaming,
+                app_handle_clone,
+            );
+        });
+    }
+
+    {
+        let device_id_clone = device_id.to_string();
+        let app_handle_clone = app_handle.clone();
+        let control_streaming = streaming.clone();
+
+        thread::spawn(move || {
+            control_reader_loop(
+                device_id_clone,
+                control_socket_reader,
+                control_streaming,
+                app_handle_clone,
+            );
+        });
+    }
+
     Ok(ScrcpyStatus {
         running: true,
         device_id: Some(device_id.to_string()),
         port: Some(video_port),
         codec_info: None,
+        audio_codec,
+        audio_sample_rate,
+        audio_channels,
+        current_bit_rate: Some(config.bit_rate),
+        bytes_per_sec: Some(0),
     })
 }
 
@@ -350,6 +565,7 @@ fn decode_and_stream(
     last_sps: Arc<Mutex<Option<Vec<u8>>>>,
     last_pps: Arc<Mutex<Option<Vec<u8>>>>,
     last_idr: Arc<Mutex<Option<Vec<u8>>>>,
+    stream_stats: Arc<Mutex<StreamStats>>,
 ) {
     // Read device name (64 bytes)
     let mut device_name = [0u8; 64];
@@ -363,125 +579,177 @@ fn decode_and_stream(
         return;
     }
 
-    // We don't need OpenH264 anymore!
-    // Just a buffer to hold incoming stream
-    let mut buffer = vec![0u8; 65536];
-    let mut nal_buffer: Vec<u8> = Vec::with_capacity(1024 * 1024);
+    socket
+        .set_read_timeout(Some(Duration::from_millis(5000)))
+        .ok();
+
+    let sanitized_id = device_id.replace('.', "_").replace(':', "_");
 
     loop {
-        // Check if still streaming
-        {
-            if !*streaming.lock().unwrap() {
-                break;
+        if !*streaming.lock().unwrap() {
+            break;
+        }
+
+        // Frame meta header (requires send_frame_meta=true): 8-byte PTS with the top two bits
+        // used as the config/key-frame flags, followed by a 4-byte big-endian packet length.
+        let mut meta = [0u8; 12];
+        match socket.read_exact(&mut meta) {
+            Ok(_) => {}
+            Err(e)
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                continue
             }
+            Err(_) => break,
         }
 
-        // Read from socket
-        match socket.read(&mut buffer) {
-            Ok(n) if n > 0 => {
-                nal_buffer.extend_from_slice(&buffer[..n]);
-
-                // Extract and emit all complete NAL units
-                while let Some(nal_data) = extract_next_nal(&mut nal_buffer) {
-                    // Cache SPS/PPS headers
-                    if nal_data.len() > 4 {
-                        // Find NAL type (usually byte 4 or 3 depending on start code)
-                        let nal_type_byte = if nal_data[2] == 0 && nal_data[3] == 1 {
-                            nal_data[4]
-                        } else {
-                            nal_data[3]
-                        };
-                        let nal_type = nal_type_byte & 0x1F;
-
-                        if nal_type == 7 {
-                            *last_sps.lock().unwrap() = Some(nal_data.clone());
-                        } else if nal_type == 8 {
-                            *last_pps.lock().unwrap() = Some(nal_data.clone());
-                        } else if nal_type == 5 {
-                            *last_idr.lock().unwrap() = Some(nal_data.clone());
-                        }
-                    }
+        let pts_flags = u64::from_be_bytes(meta[0..8].try_into().unwrap());
+        let is_config = pts_flags & (1 << 63) != 0;
+        let is_keyframe = pts_flags & (1 << 62) != 0;
+        let pts_us = pts_flags & !(0b11u64 << 62);
+        let length = u32::from_be_bytes([meta[8], meta[9], meta[10], meta[11]]) as usize;
+        if length > MAX_VIDEO_PAYLOAD_SIZE {
+            break;
+        }
 
-                    // Encode to Base64 (raw H.264 with start codes)
-                    let base64_data = base64::Engine::encode(
-                        &base64::engine::general_purpose::STANDARD,
-                        &nal_data,
-                    );
+        let mut payload = vec![0u8; length];
+        if socket.read_exact(&mut payload).is_err() {
+            break;
+        }
 
-                    // Emit 'scrcpy-h264-frame-{device_id}'
-                    // Sanitize device_id for Tauri event name requirements (alphanumeric, -, /, :, _)
-                    let sanitized_id = device_id.replace('.', "_").replace(':', "_");
-                    let _ = app_handle.emit(&format!("scrcpy-frame-{}", sanitized_id), base64_data);
-                }
+        // Cache SPS/PPS/IDR and tee to any active recorder, one NAL unit at a time.
+        for (nal_type, nal_data) in split_nal_units(&payload) {
+            if nal_type == 7 {
+                *last_sps.lock().unwrap() = Some(nal_data.to_vec());
+            } else if nal_type == 8 {
+                *last_pps.lock().unwrap() = Some(nal_data.to_vec());
+            } else if nal_type == 5 {
+                *last_idr.lock().unwrap() = Some(nal_data.to_vec());
             }
-            Ok(_) => {
-                // Connection closed (0 bytes)
 
-                break;
-            }
-            Err(e) => {
-                if e.kind() != std::io::ErrorKind::WouldBlock
-                    && e.kind() != std::io::ErrorKind::TimedOut
-                {
-                    break;
-                }
-            }
+            // No-op unless a recording is active for this device.
+            crate::services::recorder::tee_nal(&device_id, nal_data, nal_type);
         }
+
+        stream_stats.lock().unwrap().record_frame(payload.len());
+
+        // Encode to Base64 (raw H.264 with start codes)
+        let base64_data =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &payload);
+
+        let _ = app_handle.emit(
+            &format!("scrcpy-frame-{}", sanitized_id),
+            ScrcpyFramePayload {
+                data: base64_data,
+                pts_us,
+                is_config,
+                is_keyframe,
+            },
+        );
     }
 }
 
-/// Extract next NAL unit from buffer and prepend annex-b start code
-fn extract_next_nal(nal_buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
-    // NAL start codes are 00 00 00 01 or 00 00 01
-
-    // Helper to find start code sequence
-    let find_start = |data: &[u8]| -> Option<usize> {
-        for i in 0..data.len().saturating_sub(3) {
-            if data[i] == 0 && data[i + 1] == 0 {
-                if data[i + 2] == 1 {
-                    return Some(i); // 00 00 01
-                }
-                if data.len() > i + 3 && data[i + 2] == 0 && data[i + 3] == 1 {
-                    return Some(i); // 00 00 00 01
-                }
+/// Split a single frame-meta access unit into its constituent Annex-B NAL units, returning each
+/// unit's type and its bytes (start code included, matching what `tee_nal`/the SPS/PPS caches expect).
+fn split_nal_units(payload: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 2 < payload.len() {
+        if payload[i] == 0 && payload[i + 1] == 0 {
+            if payload[i + 2] == 1 {
+                starts.push(i);
+                i += 3;
+                continue;
+            }
+            if i + 3 < payload.len() && payload[i + 2] == 0 && payload[i + 3] == 1 {
+                starts.push(i);
+                i += 4;
+                continue;
             }
         }
-        None
-    };
+        i += 1;
+    }
 
-    let start_idx = match find_start(nal_buffer) {
-        Some(idx) => idx,
-        None => return None, // No start code yet
-    };
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &start)| {
+            let end = starts.get(idx + 1).copied().unwrap_or(payload.len());
+            let nal = &payload[start..end];
+            let prefix_len = if nal.len() > 3 && nal[2] == 0 && nal[3] == 1 {
+                4
+            } else {
+                3
+            };
+            if nal.len() <= prefix_len {
+                return None;
+            }
+            Some((nal[prefix_len] & 0x1F, nal))
+        })
+        .collect()
+}
 
-    // Before the start code is garbage or previous data?
-    // Usually we drain up to start code.
-    if start_idx > 0 {
-        nal_buffer.drain(..start_idx);
+/// Stream audio packets (Opus/AAC/raw) to the frontend over the dedicated audio socket
+fn audio_decode_and_stream(
+    device_id: String,
+    mut socket: TcpStream,
+    streaming: Arc<Mutex<bool>>,
+    app_handle: AppHandle,
+) {
+    // scrcpy sends a 4-byte codec id before the audio packet stream begins.
+    let mut codec_header = [0u8; 4];
+    if socket.read_exact(&mut codec_header).is_err() {
+        return;
     }
 
-    // Now nal_buffer starts with 00 ...
-    // We need to find the NEXT start code to define the END of this NAL
-    // Skip the current start code prefix (3 or 4 bytes)
-    let prefix_len = if nal_buffer.len() > 3 && nal_buffer[2] == 0 && nal_buffer[3] == 1 {
-        4
-    } else {
-        3
-    };
+    socket
+        .set_read_timeout(Some(Duration::from_millis(5000)))
+        .ok();
 
-    // Search for next start code after current prefix
-    let end_idx = match find_start(&nal_buffer[prefix_len..]) {
-        Some(offset) => prefix_len + offset,
-        None => return None, // Incomplete NAL
-    };
+    let sanitized_id = device_id.replace('.', "_").replace(':', "_");
+
+    loop {
+        if !*streaming.lock().unwrap() {
+            break;
+        }
+
+        // Frame meta header: 8-byte PTS (high bit flags a config packet) + 4-byte packet size.
+        let mut meta = [0u8; 12];
+        match socket.read_exact(&mut meta) {
+            Ok(_) => {}
+            Err(e)
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                continue
+            }
+            Err(_) => break,
+        }
 
-    // Extract complete NAL (including start code)
-    let nal_unit = nal_buffer[..end_idx].to_vec();
+        let is_config = meta[0] & 0x80 != 0;
+        let size = u32::from_be_bytes([meta[8], meta[9], meta[10], meta[11]]) as usize;
+        if size > MAX_AUDIO_PAYLOAD_SIZE {
+            break;
+        }
 
-    // Remove from buffer
-    nal_buffer.drain(..end_idx);
+        let mut packet = vec![0u8; size];
+        if socket.read_exact(&mut packet).is_err() {
+            break;
+        }
 
-    Some(nal_unit)
+        let base64_data =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &packet);
+
+        // Config packets carry decoder setup data (e.g. the AAC ASC) rather than audio samples,
+        // so the frontend needs to tell them apart to initialize its decoder correctly.
+        let event = if is_config {
+            format!("scrcpy-audio-config-{}", sanitized_id)
+        } else {
+            format!("scrcpy-audio-{}", sanitized_id)
+        };
+        let _ = app_handle.emit(&event, base64_data);
+    }
 }
 
 // Removing unused functions try_decode_frame and yuv_to_jpeg
@@ -513,6 +781,123 @@ pub fn stop_server(device_id: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Feed the adaptive bitrate controller a stats report from the frontend decoder, stepping the
+/// target bitrate (and, once that floors out, `max_size`) down when the link looks unhealthy, or
+/// back up toward the original ceiling after a sustained healthy streak. Since scrcpy v2.7 can't
+/// retune a live encoder, an actual change is applied by restarting the server. `start_server`
+/// passes `max_size` to the server but not `bit_rate`, so the encoder keeps its default bitrate
+/// across restarts and only a `max_size` step changes what it produces.
+pub fn report_client_stats(
+    device_id: &str,
+    decode_latency_ms: u32,
+    dropped_frames: u32,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    let restart_plan = {
+        let mut sessions = SCRCPY_SESSIONS.lock().unwrap();
+        let session = match sessions.get_mut(device_id) {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+
+        let jitter_ms = session.stream_stats.lock().unwrap().jitter_ms_ema;
+        let mut controller = session.bitrate.lock().unwrap();
+
+        if controller.last_adjusted_at.elapsed() < ADJUST_COOLDOWN {
+            None
+        } else if decode_latency_ms >= LATENCY_UNHEALTHY_MS
+            || dropped_frames > 0
+            || jitter_ms >= JITTER_UNHEALTHY_MS
+        {
+            controller.healthy_streak = 0;
+
+            let stepped_bit_rate =
+                ((controller.current_bit_rate as f64 * BITRATE_STEP_DOWN) as u32).max(MIN_BIT_RATE);
+            let new_max_size = if stepped_bit_rate == controller.current_bit_rate {
+                // Already at the bitrate floor; shrink the frame size instead.
+                (session.config.max_size / 2).max(MIN_MAX_SIZE)
+            } else {
+                session.config.max_size
+            };
+
+            if stepped_bit_rate == controller.current_bit_rate && new_max_size == session.config.max_size {
+                None
+            } else {
+                controller.current_bit_rate = stepped_bit_rate;
+                controller.last_adjusted_at = std::time::Instant::now();
+                Some((stepped_bit_rate, new_max_size))
+            }
+        } else {
+            controller.healthy_streak += 1;
+            if controller.healthy_streak >= HEALTHY_STREAK_TO_STEP_UP
+                && controller.current_bit_rate < controller.ceiling_bit_rate
+            {
+                let stepped_bit_rate = ((controller.current_bit_rate as f64 * BITRATE_STEP_UP) as u32)
+                    .min(controller.ceiling_bit_rate);
+                controller.healthy_streak = 0;
+                controller.current_bit_rate = stepped_bit_rate;
+                controller.last_adjusted_at = std::time::Instant::now();
+                Some((stepped_bit_rate, session.config.max_size))
+            } else {
+                None
+            }
+        }
+    };
+
+    if let Some((new_bit_rate, new_max_size)) = restart_plan {
+        restart_with_bitrate(device_id, new_bit_rate, new_max_size, app_handle)?;
+    }
+
+    Ok(())
+}
+
+/// Restart a running session with a new `bit_rate`/`max_size`, carrying over the rest of its
+/// config plus the cached SPS/PPS so a client can resync via `sync_session` before the restarted
+/// encoder has produced its own first keyframe.
+fn restart_with_bitrate(
+    device_id: &str,
+    new_bit_rate: u32,
+    new_max_size: u32,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    let (mut new_config, cached_sps, cached_pps, bitrate) = {
+        let sessions = SCRCPY_SESSIONS.lock().unwrap();
+        let session = sessions
+            .get(device_id)
+            .ok_or_else(|| AppError::new("NOT_STREAMING", "No active scrcpy session"))?;
+        (
+            session.config.clone(),
+            session.last_sps.lock().unwrap().clone(),
+            session.last_pps.lock().unwrap().clone(),
+            session.bitrate.lock().unwrap().clone(),
+        )
+    };
+
+    new_config.bit_rate = new_bit_rate;
+    new_config.max_size = new_max_size;
+
+    stop_server(device_id)?;
+    start_server(device_id, new_config, app_handle)?;
+
+    {
+        let sessions = SCRCPY_SESSIONS.lock().unwrap();
+        if let Some(session) = sessions.get(device_id) {
+            if cached_sps.is_some() {
+                *session.last_sps.lock().unwrap() = cached_sps;
+            }
+            if cached_pps.is_some() {
+                *session.last_pps.lock().unwrap() = cached_pps;
+            }
+            *session.bitrate.lock().unwrap() = bitrate;
+        }
+    }
+
+    let sanitized_id = device_id.replace('.', "_").replace(':', "_");
+    let _ = app_handle.emit(&format!("scrcpy-restarted-{}", sanitized_id), ());
+
+    Ok(())
+}
+
 /// Get scrcpy status
 pub fn get_status(device_id: &str) -> ScrcpyStatus {
     let sessions = SCRCPY_SESSIONS.lock().unwrap();
@@ -522,6 +907,11 @@ pub fn get_status(device_id: &str) -> ScrcpyStatus {
             device_id: Some(device_id.to_string()),
             port: Some(session.video_port),
             codec_info: None,
+            audio_codec: session.audio_codec.clone(),
+            audio_sample_rate: session.audio_sample_rate,
+            audio_channels: session.audio_channels,
+            current_bit_rate: Some(session.bitrate.lock().unwrap().current_bit_rate),
+            bytes_per_sec: Some(session.stream_stats.lock().unwrap().bytes_per_sec),
         }
     } else {
         ScrcpyStatus {
@@ -529,6 +919,11 @@ pub fn get_status(device_id: &str) -> ScrcpyStatus {
             device_id: None,
             port: None,
             codec_info: None,
+            audio_codec: None,
+            audio_sample_rate: None,
+            audio_channels: None,
+            current_bit_rate: None,
+            bytes_per_sec: None,
         }
     }
 }
@@ -538,6 +933,17 @@ pub fn read_video_frame(_device_id: &str) -> Result<Vec<u8>, AppError> {
     Err(AppError::new("DEPRECATED", "Use event-based streaming"))
 }
 
+/// Snapshot the cached SPS/PPS/IDR for a session, if all three have arrived yet. Used by the
+/// snapshot service to decode a still picture without tapping into the live decode thread.
+pub(crate) fn cached_headers(device_id: &str) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let sessions = SCRCPY_SESSIONS.lock().unwrap();
+    let session = sessions.get(device_id)?;
+    let sps = session.last_sps.lock().unwrap().clone()?;
+    let pps = session.last_pps.lock().unwrap().clone()?;
+    let idr = session.last_idr.lock().unwrap().clone()?;
+    Some((sps, pps, idr))
+}
+
 /// Send control event
 pub fn send_control_event(device_id: &str, event_type: u8, data: &[u8]) -> Result<(), AppError> {
     let sessions = SCRCPY_SESSIONS.lock().unwrap();
@@ -560,6 +966,114 @@ pub fn send_control_event(device_id: &str, event_type: u8, data: &[u8]) -> Resul
     Ok(())
 }
 
+const CONTROL_MSG_TYPE_INJECT_TEXT: u8 = 1;
+const CONTROL_MSG_TYPE_GET_CLIPBOARD: u8 = 8;
+const CONTROL_MSG_TYPE_SET_CLIPBOARD: u8 = 9;
+
+const DEVICE_MSG_TYPE_CLIPBOARD: u8 = 0;
+const DEVICE_MSG_TYPE_ACK_CLIPBOARD: u8 = 1;
+
+static CLIPBOARD_SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// Inject a UTF-8 string as if typed, via INJECT_TEXT: type(1) + length(4, BE) + UTF-8 bytes.
+pub fn inject_text(device_id: &str, text: &str) -> Result<(), AppError> {
+    let text_bytes = text.as_bytes();
+    let mut data = Vec::with_capacity(4 + text_bytes.len());
+    data.extend_from_slice(&(text_bytes.len() as u32).to_be_bytes());
+    data.extend_from_slice(text_bytes);
+
+    send_control_event(device_id, CONTROL_MSG_TYPE_INJECT_TEXT, &data)
+}
+
+/// Push `text` to the device clipboard via SET_CLIPBOARD: 8-byte sequence + 1-byte paste flag +
+/// 4-byte length + UTF-8 bytes. The device ACKs with the same sequence on the control socket,
+/// which `control_reader_loop` reads but doesn't currently surface (this call is fire-and-forget).
+pub fn set_device_clipboard(device_id: &str, text: &str, paste: bool) -> Result<(), AppError> {
+    let sequence = CLIPBOARD_SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let text_bytes = text.as_bytes();
+
+    let mut data = Vec::with_capacity(8 + 1 + 4 + text_bytes.len());
+    data.extend_from_slice(&sequence.to_be_bytes());
+    data.push(paste as u8);
+    data.extend_from_slice(&(text_bytes.len() as u32).to_be_bytes());
+    data.extend_from_slice(text_bytes);
+
+    send_control_event(device_id, CONTROL_MSG_TYPE_SET_CLIPBOARD, &data)
+}
+
+/// Ask the device to push its clipboard back over the control socket. `copy_key` is 0 (send as
+/// is), 1 (simulate a copy first) or 2 (simulate a cut first). The response arrives
+/// asynchronously as a `scrcpy-clipboard-{device_id}` event, read by `control_reader_loop`.
+pub fn get_device_clipboard(device_id: &str, copy_key: u8) -> Result<(), AppError> {
+    send_control_event(device_id, CONTROL_MSG_TYPE_GET_CLIPBOARD, &[copy_key])
+}
+
+/// Read device->client control messages (clipboard content and clipboard-set ACKs) and emit the
+/// clipboard ones as `scrcpy-clipboard-{device_id}` events.
+fn control_reader_loop(
+    device_id: String,
+    mut socket: TcpStream,
+    streaming: Arc<Mutex<bool>>,
+    app_handle: AppHandle,
+) {
+    socket
+        .set_read_timeout(Some(Duration::from_millis(5000)))
+        .ok();
+
+    let sanitized_id = device_id.replace('.', "_").replace(':', "_");
+
+    loop {
+        if !*streaming.lock().unwrap() {
+            break;
+        }
+
+        let mut msg_type = [0u8; 1];
+        match socket.read_exact(&mut msg_type) {
+            Ok(_) => {}
+            Err(e)
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                continue
+            }
+            Err(_) => break,
+        }
+
+        match msg_type[0] {
+            DEVICE_MSG_TYPE_CLIPBOARD => {
+                let mut len_buf = [0u8; 4];
+                if socket.read_exact(&mut len_buf).is_err() {
+                    break;
+                }
+                let len = u32::from_be_bytes(len_buf) as usize;
+                if len > MAX_CLIPBOARD_PAYLOAD_SIZE {
+                    break;
+                }
+
+                let mut text_buf = vec![0u8; len];
+                if socket.read_exact(&mut text_buf).is_err() {
+                    break;
+                }
+
+                if let Ok(text) = String::from_utf8(text_buf) {
+                    let _ = app_handle.emit(&format!("scrcpy-clipboard-{}", sanitized_id), text);
+                }
+            }
+            DEVICE_MSG_TYPE_ACK_CLIPBOARD => {
+                // Acknowledges a prior set_device_clipboard by sequence; set_device_clipboard is
+                // fire-and-forget, so we just drain the 8-byte sequence and move on.
+                let mut seq_buf = [0u8; 8];
+                if socket.read_exact(&mut seq_buf).is_err() {
+                    break;
+                }
+            }
+            // Unknown message type: we don't know its length, so the stream can't be
+            // resynchronized. Stop reading rather than risk misparsing the rest.
+            _ => break,
+        }
+    }
+}
+
 /// Synchronize a new client by re-emitting cached SPS/PPS/IDR headers to a private event channel
 pub fn sync_session(
     device_id: &str,
@@ -580,19 +1094,49 @@ pub fn sync_session(
         if let Some(sps_data) = sps {
             let base64_sps =
                 base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &sps_data);
-            app_handle.emit(&sync_event, base64_sps).ok();
+            app_handle
+                .emit(
+                    &sync_event,
+                    ScrcpyFramePayload {
+                        data: base64_sps,
+                        pts_us: 0,
+                        is_config: true,
+                        is_keyframe: false,
+                    },
+                )
+                .ok();
         }
 
         if let Some(pps_data) = pps {
             let base64_pps =
                 base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &pps_data);
-            app_handle.emit(&sync_event, base64_pps).ok();
+            app_handle
+                .emit(
+                    &sync_event,
+                    ScrcpyFramePayload {
+                        data: base64_pps,
+                        pts_us: 0,
+                        is_config: true,
+                        is_keyframe: false,
+                    },
+                )
+                .ok();
         }
 
         if let Some(idr_data) = idr {
             let base64_idr =
                 base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &idr_data);
-            app_handle.emit(&sync_event, base64_idr).ok();
+            app_handle
+                .emit(
+                    &sync_event,
+                    ScrcpyFramePayload {
+                        data: base64_idr,
+                        pts_us: 0,
+                        is_config: false,
+                        is_keyframe: true,
+                    },
+                )
+                .ok();
         }
     }
     Ok(())
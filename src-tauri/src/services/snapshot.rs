@@ -0,0 +1,148 @@
+// Snapshot Service - On-demand JPEG stills decoded from the live scrcpy stream
+// Decodes off the scrcpy session's cached SPS/PPS/IDR on a small worker pool so a snapshot
+// request never blocks the hot decode_and_stream path.
+
+use crate::error::AppError;
+use crate::services::scrcpy;
+use image::codecs::jpeg::JpegEncoder;
+use image::ImageBuffer;
+use openh264::decoder::Decoder;
+use openh264::formats::YUVSource;
+use openh264::nal_units;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for a keyframe to land in the scrcpy session's cache before giving up.
+const IDR_WAIT_TIMEOUT: Duration = Duration::from_secs(3);
+const IDR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+lazy_static::lazy_static! {
+    /// Small pool dedicated to snapshot decoding, kept separate from the scrcpy decode threads
+    /// so a slow/expensive snapshot never backs up the live preview.
+    static ref SNAPSHOT_POOL: threadpool::ThreadPool = threadpool::ThreadPool::new(2);
+}
+
+/// Result of a successful snapshot: either inline JPEG data or the path it was written to,
+/// depending on whether the caller asked for a file.
+pub enum SnapshotOutput {
+    Base64(String),
+    Path(String),
+}
+
+/// Decode a single still frame from `device_id`'s live scrcpy stream.
+///
+/// `quality` is the JPEG quality (1-100). `scale` resizes the decoded picture (1.0 = native
+/// size). If `output_path` is given, the JPEG is written there and the path is returned;
+/// otherwise the JPEG bytes are returned as base64.
+pub fn capture_snapshot(
+    device_id: &str,
+    quality: u8,
+    scale: f32,
+    output_path: Option<PathBuf>,
+) -> Result<SnapshotOutput, AppError> {
+    let (sps, pps, idr) = wait_for_keyframe(device_id)?;
+
+    let mut bitstream = Vec::with_capacity(sps.len() + pps.len() + idr.len());
+    bitstream.extend_from_slice(&sps);
+    bitstream.extend_from_slice(&pps);
+    bitstream.extend_from_slice(&idr);
+
+    // Hand the actual decode off to the worker pool; the caller just waits on the channel.
+    let (tx, rx) = mpsc::channel();
+    SNAPSHOT_POOL.execute(move || {
+        let _ = tx.send(decode_to_jpeg(&bitstream, quality, scale));
+    });
+
+    let jpeg_bytes = rx
+        .recv()
+        .map_err(|_| AppError::new("SNAPSHOT_WORKER_GONE", "Snapshot worker panicked"))??;
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(&path, &jpeg_bytes).map_err(|e| {
+                AppError::new(
+                    "SNAPSHOT_WRITE_FAILED",
+                    &format!("Failed to write snapshot: {}", e),
+                )
+            })?;
+            Ok(SnapshotOutput::Path(path.to_string_lossy().to_string()))
+        }
+        None => {
+            let base64_data =
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &jpeg_bytes);
+            Ok(SnapshotOutput::Base64(base64_data))
+        }
+    }
+}
+
+/// Poll the scrcpy session's SPS/PPS/IDR cache until a keyframe is available, to cover the brief
+/// window right after a session starts before the first IDR has arrived.
+fn wait_for_keyframe(device_id: &str) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), AppError> {
+    let deadline = Instant::now() + IDR_WAIT_TIMEOUT;
+    loop {
+        if let Some(headers) = scrcpy::cached_headers(device_id) {
+            return Ok(headers);
+        }
+        if Instant::now() >= deadline {
+            return Err(AppError::new(
+                "NO_KEYFRAME",
+                "No keyframe available yet for this session",
+            ));
+        }
+        thread::sleep(IDR_POLL_INTERVAL);
+    }
+}
+
+/// Decode an SPS+PPS+IDR Annex-B bitstream to a single picture and encode it as JPEG.
+fn decode_to_jpeg(bitstream: &[u8], quality: u8, scale: f32) -> Result<Vec<u8>, AppError> {
+    let mut decoder =
+        Decoder::new().map_err(|e| AppError::new("DECODER_INIT_FAILED", &format!("{}", e)))?;
+
+    let mut last_yuv = None;
+    for nal in nal_units(bitstream) {
+        if let Some(yuv) = decoder
+            .decode(nal)
+            .map_err(|e| AppError::new("DECODE_FAILED", &format!("{}", e)))?
+        {
+            last_yuv = Some(yuv);
+        }
+    }
+    let yuv = last_yuv.ok_or_else(|| AppError::new("DECODE_FAILED", "Decoder produced no picture"))?;
+
+    let width = yuv.width() as u32;
+    let height = yuv.height() as u32;
+
+    let mut rgb = vec![0u8; (width * height * 3) as usize];
+    yuv.write_rgb8(&mut rgb);
+
+    let image = ImageBuffer::<image::Rgb<u8>, _>::from_raw(width, height, rgb)
+        .ok_or_else(|| AppError::new("DECODE_FAILED", "Failed to build RGB image"))?;
+
+    let image = if (scale - 1.0).abs() > f32::EPSILON {
+        let new_width = ((width as f32) * scale).round().max(1.0) as u32;
+        let new_height = ((height as f32) * scale).round().max(1.0) as u32;
+        image::imageops::resize(
+            &image,
+            new_width,
+            new_height,
+            image::imageops::FilterType::Triangle,
+        )
+    } else {
+        image
+    };
+
+    let mut jpeg_bytes = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut jpeg_bytes, quality.clamp(1, 100));
+    encoder
+        .encode(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            image::ColorType::Rgb8,
+        )
+        .map_err(|e| AppError::new("JPEG_ENCODE_FAILED", &format!("{}", e)))?;
+
+    Ok(jpeg_bytes)
+}
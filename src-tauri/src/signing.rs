@@ -0,0 +1,316 @@
+// APK Signing - zipalign + apksigner pipeline
+// Page-aligns and signs an unsigned or debug-signed APK so it can be installed, mirroring the
+// NDK apk builder flow (zipalign -> apksigner sign -> apksigner verify).
+
+use crate::command_utils::hidden_command;
+use crate::error::{AdbError, AppError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Location and credentials of the signing key to use. If `keystore_path` is absent, an
+/// ephemeral debug keystore is generated (or reused) instead.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyConfig {
+    pub keystore_path: Option<String>,
+    pub alias: Option<String>,
+    pub keystore_password: Option<String>,
+    pub key_password: Option<String>,
+}
+
+/// APK signature scheme, as reported by `apksigner verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningScheme {
+    V1,
+    V2,
+    V3,
+    V4,
+}
+
+/// Result of checking an APK's signing status.
+#[derive(Debug, Clone, Serialize)]
+pub struct SigningInfo {
+    pub signed: bool,
+    pub schemes: Vec<SigningScheme>,
+    pub certificate_fingerprint: Option<String>,
+}
+
+/// Result of a sign operation.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignResult {
+    pub success: bool,
+    pub apk_path: String,
+    pub message: String,
+    pub info: Option<SigningInfo>,
+}
+
+/// A user-writable data directory for the app, computed without Tauri's path resolver (no
+/// `AppHandle` is threaded through here). Mirrors the OS conventions Tauri itself uses for
+/// `app_data_dir`.
+fn managed_data_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("adb-compass")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        home_dir()
+            .join("Library")
+            .join("Application Support")
+            .join("adb-compass")
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home_dir().join(".local").join("share"))
+            .join("adb-compass")
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Signs APKs using the bundled (or PATH-resolved) `zipalign`/`apksigner` build tools.
+pub struct ApkSigner {
+    zipalign_path: PathBuf,
+    apksigner_path: PathBuf,
+}
+
+impl Default for ApkSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApkSigner {
+    pub fn new() -> Self {
+        Self {
+            zipalign_path: Self::discover_tool("zipalign"),
+            apksigner_path: Self::discover_tool("apksigner"),
+        }
+    }
+
+    /// Look for a bundled copy of the tool next to the app binary, falling back to PATH.
+    fn discover_tool(name: &str) -> PathBuf {
+        Self::find_bundled_tool(name).unwrap_or_else(|| PathBuf::from(name))
+    }
+
+    fn find_bundled_tool(name: &str) -> Option<PathBuf> {
+        let exe_path = std::env::current_exe().ok()?;
+        let exe_dir = exe_path.parent()?;
+        let tool_name = if cfg!(target_os = "windows") {
+            format!("{}.exe", name)
+        } else {
+            name.to_string()
+        };
+
+        let possible_paths = [
+            // Development paths
+            exe_dir
+                .parent()
+                .and_then(|p| p.parent())
+                .map(|p| p.join("binaries").join(&tool_name)),
+            exe_dir
+                .parent()
+                .and_then(|p| p.parent())
+                .and_then(|p| p.parent())
+                .map(|p| p.join("src-tauri").join("binaries").join(&tool_name)),
+            // Production paths
+            Some(exe_dir.join("resources").join("binaries").join(&tool_name)),
+            Some(exe_dir.join("binaries").join(&tool_name)),
+            // CWD fallback
+            Some(PathBuf::from("binaries").join(&tool_name)),
+        ];
+
+        possible_paths.into_iter().flatten().find(|p| p.exists())
+    }
+
+    /// Page-align the APK and sign it with the given key (or an ephemeral debug keystore if
+    /// `key_config` is absent). Returns the path to the aligned-and-signed APK.
+    pub fn sign(&self, apk_path: &str, key_config: Option<KeyConfig>) -> Result<SignResult, AppError> {
+        let aligned_path = self.zipalign(apk_path)?;
+        let (keystore_path, alias, keystore_password, key_password) = match key_config {
+            Some(cfg) if cfg.keystore_path.is_some() => (
+                cfg.keystore_path.unwrap(),
+                cfg.alias.unwrap_or_else(|| "androiddebugkey".to_string()),
+                cfg.keystore_password.unwrap_or_else(|| "android".to_string()),
+                cfg.key_password.unwrap_or_else(|| "android".to_string()),
+            ),
+            _ => self.ensure_debug_keystore()?,
+        };
+
+        let output = hidden_command(&self.apksigner_path)
+            .args([
+                "sign",
+                "--ks",
+                &keystore_path,
+                "--ks-key-alias",
+                &alias,
+                "--ks-pass",
+                &format!("pass:{}", keystore_password),
+                "--key-pass",
+                &format!("pass:{}", key_password),
+                &aligned_path,
+            ])
+            .output()
+            .map_err(|e| {
+                AppError::from(AdbError::ExecutionFailed(format!(
+                    "Failed to run apksigner: {}",
+                    e
+                )))
+            })?;
+
+        if !output.status.success() {
+            return Ok(SignResult {
+                success: false,
+                apk_path: aligned_path,
+                message: String::from_utf8_lossy(&output.stderr).to_string(),
+                info: None,
+            });
+        }
+
+        let info = self.verify(&aligned_path)?;
+        Ok(SignResult {
+            success: true,
+            apk_path: aligned_path,
+            message: "APK signed successfully".to_string(),
+            info: Some(info),
+        })
+    }
+
+    /// Run `zipalign -p 4` to page-align the APK, writing the result alongside the input.
+    fn zipalign(&self, apk_path: &str) -> Result<String, AppError> {
+        let aligned_path = Path::new(apk_path).with_extension("aligned.apk");
+        let aligned_str = aligned_path.to_string_lossy().to_string();
+
+        let output = hidden_command(&self.zipalign_path)
+            .args(["-f", "-p", "4", apk_path, &aligned_str])
+            .output()
+            .map_err(|e| {
+                AppError::from(AdbError::ExecutionFailed(format!(
+                    "Failed to run zipalign: {}",
+                    e
+                )))
+            })?;
+
+        if !output.status.success() {
+            return Err(AppError::from(AdbError::ExecutionFailed(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )));
+        }
+
+        Ok(aligned_str)
+    }
+
+    /// Generate an ephemeral debug keystore on first run (via `keytool`), reusing the
+    /// well-known Android debug key alias/passwords so the result installs like a normal
+    /// debug build. Persisted in the app's data dir and reused across calls once created.
+    fn ensure_debug_keystore(&self) -> Result<(String, String, String, String), AppError> {
+        let keystore_path = managed_data_dir().join("debug.keystore");
+        let keystore_str = keystore_path.to_string_lossy().to_string();
+
+        if !keystore_path.exists() {
+            if let Some(parent) = keystore_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    AppError::from(AdbError::ExecutionFailed(format!(
+                        "Failed to create keystore directory: {}",
+                        e
+                    )))
+                })?;
+            }
+            let output = hidden_command("keytool")
+                .args([
+                    "-genkeypair",
+                    "-keystore",
+                    &keystore_str,
+                    "-alias",
+                    "androiddebugkey",
+                    "-storepass",
+                    "android",
+                    "-keypass",
+                    "android",
+                    "-keyalg",
+                    "RSA",
+                    "-keysize",
+                    "2048",
+                    "-validity",
+                    "10950",
+                    "-dname",
+                    "CN=Android Debug,O=Android,C=US",
+                ])
+                .output()
+                .map_err(|e| {
+                    AppError::from(AdbError::ExecutionFailed(format!(
+                        "Failed to run keytool: {}",
+                        e
+                    )))
+                })?;
+
+            if !output.status.success() {
+                return Err(AppError::from(AdbError::ExecutionFailed(
+                    String::from_utf8_lossy(&output.stderr).to_string(),
+                )));
+            }
+        }
+
+        Ok((
+            keystore_str,
+            "androiddebugkey".to_string(),
+            "android".to_string(),
+            "android".to_string(),
+        ))
+    }
+
+    /// Run `apksigner verify --print-certs -v` and parse the signing schemes and the first
+    /// certificate's SHA-256 fingerprint out of its output.
+    pub fn verify(&self, apk_path: &str) -> Result<SigningInfo, AppError> {
+        let output = hidden_command(&self.apksigner_path)
+            .args(["verify", "--print-certs", "-v", apk_path])
+            .output()
+            .map_err(|e| {
+                AppError::from(AdbError::ExecutionFailed(format!(
+                    "Failed to run apksigner: {}",
+                    e
+                )))
+            })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let signed = output.status.success();
+
+        let mut schemes = Vec::new();
+        for line in stdout.lines() {
+            let line = line.trim();
+            if !line.ends_with(": true") {
+                continue;
+            }
+            if line.starts_with("Verified using v1 scheme") {
+                schemes.push(SigningScheme::V1);
+            } else if line.starts_with("Verified using v2 scheme") {
+                schemes.push(SigningScheme::V2);
+            } else if line.starts_with("Verified using v3 scheme") {
+                schemes.push(SigningScheme::V3);
+            } else if line.starts_with("Verified using v4 scheme") {
+                schemes.push(SigningScheme::V4);
+            }
+        }
+
+        let certificate_fingerprint = stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Signer #1 certificate SHA-256 digest: "))
+            .map(|s| s.to_string());
+
+        Ok(SigningInfo {
+            signed,
+            schemes,
+            certificate_fingerprint,
+        })
+    }
+}